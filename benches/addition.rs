@@ -0,0 +1,38 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use duckmp::memory::Word;
+use duckmp::uint::UnsignedInteger;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+fn random(rng: &mut SmallRng, len: usize) -> UnsignedInteger {
+    UnsignedInteger::from_words_iter((0..len).map(|_| Word(rng.gen()))).unwrap()
+}
+
+/// Compare the `bool` carry of `unchecked_add` against the `u128` carry chain
+fn addition(c: &mut Criterion) {
+    let mut group = c.benchmark_group("addition");
+    let mut rng = SmallRng::seed_from_u64(306);
+    for &len in &[4, 32, 256] {
+        let (a, b) = (random(&mut rng, len), random(&mut rng, len));
+        group.bench_with_input(BenchmarkId::new("bool_carry", len), &len, |bencher, _| {
+            bencher.iter_batched_ref(
+                || a.clone_with_capacity(1).unwrap(),
+                // The extra word of capacity holds any carry
+                |target| unsafe { target.unchecked_add(black_box(&b)) },
+                BatchSize::SmallInput
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("carry_chain", len), &len, |bencher, _| {
+            bencher.iter_batched_ref(
+                || a.clone_with_capacity(1).unwrap(),
+                // The extra word of capacity holds any carry
+                |target| unsafe { target.unchecked_add_carry_chain(black_box(&b)) },
+                BatchSize::SmallInput
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, addition);
+criterion_main!(benches);
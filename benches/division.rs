@@ -0,0 +1,83 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use duckmp::memory::Word;
+use duckmp::uint::{DivContext, UnsignedInteger};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+fn random(rng: &mut SmallRng, len: usize) -> UnsignedInteger {
+    UnsignedInteger::from_words_iter((0..len).map(|_| Word(rng.gen()))).unwrap()
+}
+
+fn div_context(c: &mut Criterion) {
+    let mut group = c.benchmark_group("div_context");
+    let mut rng = SmallRng::seed_from_u64(296);
+    for &len in &[4, 16, 64] {
+        let (n, d) = (random(&mut rng, len * 2), random(&mut rng, len));
+        group.bench_with_input(BenchmarkId::new("div_rem", len), &len, |b, _| {
+            b.iter(|| n.div_rem(&d).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("div_rem_with", len), &len, |b, _| {
+            b.iter_batched_ref(
+                || {
+                    // Warm up the context, so its buffers are already large enough
+                    let mut ctx = DivContext::new();
+                    n.div_rem_with(&d, &mut ctx).unwrap();
+                    ctx
+                },
+                |ctx| n.div_rem_with(&d, ctx).unwrap(),
+                BatchSize::SmallInput
+            )
+        });
+    }
+    group.finish();
+}
+
+/// A random integer with exactly `len` words, and the top bit set
+fn random_full(rng: &mut SmallRng, len: usize) -> UnsignedInteger {
+    let mut res = random(rng, len);
+    res.set_bit(len as u64 * 64 - 1, true).unwrap();
+    res
+}
+
+fn small_quotient(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small_quotient");
+    let mut rng = SmallRng::seed_from_u64(218);
+    let d = random_full(&mut rng, 16);
+    // Past `SMALL_QUOTIENT_BITS`, `div_rem` switches to Algorithm D
+    for &quotient_bits in &[0, 1, 2, 4, 8, 16] {
+        let mut n = random_full(&mut rng, 16);
+        n.shl_assign(quotient_bits).unwrap();
+        group.bench_with_input(BenchmarkId::new("div_rem", quotient_bits), &quotient_bits, |b, _| {
+            b.iter(|| n.div_rem(&d).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("div_rem_small_quotient", quotient_bits), &quotient_bits, |b, _| {
+            b.iter(|| n.div_rem_small_quotient(&d).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Compare dividing by a single word, as `to_str_radix` does, against the general division
+fn div_rem_u64(c: &mut Criterion) {
+    let mut group = c.benchmark_group("div_rem_u64");
+    let mut rng = SmallRng::seed_from_u64(266);
+    let divisor = 10_000_000_000_000_000_000u64;
+    let wide_divisor = UnsignedInteger::from(divisor);
+    for &len in &[1, 4, 16, 64] {
+        let n = random(&mut rng, len);
+        group.bench_with_input(BenchmarkId::new("div_rem", len), &len, |b, _| {
+            b.iter(|| n.div_rem(&wide_divisor).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("div_rem_u64", len), &len, |b, _| {
+            b.iter_batched_ref(
+                || n.clone(),
+                |n| n.div_rem_u64(divisor).unwrap(),
+                BatchSize::SmallInput
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, div_context, small_quotient, div_rem_u64);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use duckmp::memory::Word;
+use duckmp::uint::UnsignedInteger;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+fn random(rng: &mut SmallRng, len: usize) -> UnsignedInteger {
+    UnsignedInteger::from_words_iter((0..len).map(|_| Word(rng.gen()))).unwrap()
+}
+
+fn format_decimal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("format_decimal");
+    group.sample_size(10);
+    let mut rng = SmallRng::seed_from_u64(229);
+    // About 10k, 100k and 400k decimal digits
+    for &len in &[512, 5120, 20480] {
+        let value = random(&mut rng, len);
+        group.bench_with_input(BenchmarkId::new("to_str_radix", len), &value, |b, value| {
+            b.iter(|| value.to_str_radix(10))
+        });
+        group.bench_with_input(BenchmarkId::new("to_str_radix_parallel", len), &value, |b, value| {
+            b.iter(|| value.to_str_radix_parallel(10))
+        });
+    }
+    group.finish();
+}
+
+/// Formatting a thousand decimal digits in bases without a power of two fast path
+fn format_radix(c: &mut Criterion) {
+    let mut group = c.benchmark_group("format_radix");
+    let mut rng = SmallRng::seed_from_u64(303);
+    // 52 words is just over a thousand decimal digits
+    let value = random(&mut rng, 52);
+    for &radix in &[3, 10, 36] {
+        group.bench_with_input(BenchmarkId::from_parameter(radix), &radix, |b, &radix| {
+            b.iter(|| value.to_str_radix(radix))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, format_decimal, format_radix);
+criterion_main!(benches);
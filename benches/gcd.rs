@@ -0,0 +1,25 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use duckmp::memory::Word;
+use duckmp::uint::UnsignedInteger;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+fn random(rng: &mut SmallRng, len: usize) -> UnsignedInteger {
+    UnsignedInteger::from_words_iter((0..len).map(|_| Word(rng.gen()))).unwrap()
+}
+
+/// Compare lengths on either side of `LEHMER_GCD_THRESHOLD`
+fn gcd(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gcd");
+    let mut rng = SmallRng::seed_from_u64(224);
+    for &len in &[4, 8, 12, 16, 24, 32, 128] {
+        let (a, b) = (random(&mut rng, len), random(&mut rng, len));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &len, |bencher, _| {
+            bencher.iter(|| a.gcd(&b).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, gcd);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use duckmp::memory::Word;
+use duckmp::uint::{MulConfig, UnsignedInteger, KARATSUBA_THRESHOLD};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+fn random(rng: &mut SmallRng, len: usize) -> UnsignedInteger {
+    UnsignedInteger::from_words_iter((0..len).map(|_| Word(rng.gen()))).unwrap()
+}
+
+/// Compare lengths on either side of `KARATSUBA_THRESHOLD`
+fn karatsuba(c: &mut Criterion) {
+    let mut group = c.benchmark_group("karatsuba");
+    let mut rng = SmallRng::seed_from_u64(275);
+    let schoolbook = MulConfig::new().with_karatsuba_threshold(usize::MAX);
+    for &len in &[16, 24, 32, 48, 64, 128, 256] {
+        let (a, b) = (random(&mut rng, len), random(&mut rng, len));
+        group.bench_with_input(BenchmarkId::new("schoolbook", len), &len, |bencher, _| {
+            bencher.iter(|| a.mul_with(&b, &schoolbook).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("mul", len), &len, |bencher, _| {
+            bencher.iter(|| UnsignedInteger::mul(&a, &b).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Compare thresholds for large operands, where every level of recursion
+/// chooses between splitting again and grade school multiplication
+fn karatsuba_threshold(c: &mut Criterion) {
+    let mut group = c.benchmark_group("karatsuba_threshold");
+    let mut rng = SmallRng::seed_from_u64(275);
+    let (a, b) = (random(&mut rng, 512), random(&mut rng, 512));
+    for &threshold in &[8, 16, 24, KARATSUBA_THRESHOLD, 48, 64, 96] {
+        let config = MulConfig::new().with_karatsuba_threshold(threshold);
+        group.bench_with_input(BenchmarkId::from_parameter(threshold), &threshold, |bencher, _| {
+            bencher.iter(|| a.mul_with(&b, &config).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Compare multiplying by a power of two, which is just a shift,
+/// against a general multiplier of the same length
+fn power_of_two(c: &mut Criterion) {
+    let mut group = c.benchmark_group("power_of_two");
+    let mut rng = SmallRng::seed_from_u64(295);
+    for &len in &[4, 16, 64] {
+        let a = random(&mut rng, len);
+        // Single words always use grade school multiplication
+        for &multiplier_len in &[1, len / 2] {
+            let mut power = UnsignedInteger::ZERO;
+            power.set_bit(multiplier_len as u64 * 64 - 24, true).unwrap();
+            let mut general = random(&mut rng, multiplier_len);
+            general.set_bit(multiplier_len as u64 * 64 - 24, true).unwrap();
+            let id = format!("{}x{}", len, multiplier_len);
+            group.bench_with_input(BenchmarkId::new("power_of_two", &id), &len, |bencher, _| {
+                bencher.iter(|| UnsignedInteger::mul(&a, &power).unwrap())
+            });
+            group.bench_with_input(BenchmarkId::new("general", &id), &len, |bencher, _| {
+                bencher.iter(|| UnsignedInteger::mul(&a, &general).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, karatsuba, karatsuba_threshold, power_of_two);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use duckmp::uint::UnsignedInteger;
+use num_traits::Num;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+fn random_digits(rng: &mut SmallRng, len: usize) -> String {
+    (0..len).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect()
+}
+
+/// The general parser against the exact one
+fn parse_decimal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_decimal");
+    let mut rng = SmallRng::seed_from_u64(221);
+    for &len in &[100, 1000, 10_000] {
+        let digits = random_digits(&mut rng, len);
+        group.bench_with_input(BenchmarkId::new("from_str_radix", len), &digits, |b, digits| {
+            b.iter(|| UnsignedInteger::<Vec<_>>::from_str_radix(digits, 10).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("from_decimal_exact", len), &digits, |b, digits| {
+            b.iter(|| UnsignedInteger::<Vec<_>>::from_decimal_exact(digits).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Packing hex digits directly against the general parser
+fn parse_hex(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_hex");
+    let mut rng = SmallRng::seed_from_u64(300);
+    for &len in &[64, 1024, 16_384] {
+        let digits: String = (0..len)
+            .map(|_| char::from_digit(rng.gen_range(0..16), 16).unwrap())
+            .collect();
+        group.bench_with_input(BenchmarkId::new("from_str_radix", len), &digits, |b, digits| {
+            b.iter(|| UnsignedInteger::<Vec<_>>::from_str_radix(digits, 16).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("from_hex_str", len), &digits, |b, digits| {
+            b.iter(|| UnsignedInteger::<Vec<_>>::from_hex_str(digits).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse_decimal, parse_hex);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+//! Checks that duckmp builds without the standard library (or `alloc`)
+//!
+//! Build with `cargo build --manifest-path no-std-smoke/Cargo.toml`
+#![no_std]
+use duckmp::memory::InlineWordArray;
+use duckmp::uint::UnsignedInteger;
+
+/// An integer that never touches the heap
+pub type InlineInteger = UnsignedInteger<InlineWordArray<4>>;
+
+/// Compute `(left * right + 1) mod modulus` using only inline words
+///
+/// Returns `None` if the modulus is zero.
+pub fn mul_add_one_mod(left: u64, right: u64, modulus: u64) -> Option<u64> {
+    let mut product = InlineInteger::ZERO;
+    // Two words always fit
+    product.set(left).ok()?;
+    product.mul_u64(right).ok()?;
+    product.add_u64(1).ok()?;
+    product.div_rem_u64(modulus).ok()
+}
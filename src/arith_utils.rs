@@ -11,7 +11,25 @@ pub trait ArithUtil: PrimInt + Copy {
     /// The maximum value of the type
     const MAX: Self;
     /// Calculate `ceil(log2(self))` using bitwise magic
+    ///
+    /// Since the logarithm of zero is undefined,
+    /// this returns zero for both zero and one.
     fn ceil_log2(self) -> Self;
+    /// Calculate `ceil(log2(self))`,
+    /// returning `None` for zero
+    fn checked_ceil_log2(self) -> Option<Self>;
+    /// Calculate `floor(log2(self))`,
+    /// returning `None` for zero
+    fn checked_floor_log2(self) -> Option<Self>;
+    /// Check if this is a power of two
+    ///
+    /// Zero is not a power of two.
+    fn is_power_of_two(self) -> bool;
+    /// The smallest power of two greater than or equal to this value
+    ///
+    /// Zero rounds up to one.
+    /// Panics on overflow if debug assertions are enabled.
+    fn next_power_of_two(self) -> Self;
     /// Divide using integer division,
     /// but round up instead of down
     fn divide_round_up(self, divisor: Self) -> Self;
@@ -19,22 +37,82 @@ pub trait ArithUtil: PrimInt + Copy {
 macro_rules! impl_prim_int {
     ($($target:ty),*) => {
         $(impl ArithUtil for $target {
-            const BITS: $target = <$target>::BITS;
+            const BITS: $target = <$target>::BITS as $target;
             const MIN: $target = <$target>::MIN;
             const MAX: $target = <$target>::MAX;
             #[inline]
             fn ceil_log2(self) -> $target {
-                Self::BITS - (self - 1).leading_zeros()
+                if self <= 1 {
+                    0
+                } else {
+                    unsafe {
+                        (<$target>::BITS - self.unchecked_sub(1).leading_zeros()) as $target
+                    }
+                }
+            }
+            #[inline]
+            fn checked_ceil_log2(self) -> Option<$target> {
+                if self == 0 { None } else { Some(self.ceil_log2()) }
+            }
+            #[inline]
+            fn checked_floor_log2(self) -> Option<$target> {
+                if self == 0 {
+                    None
+                } else {
+                    Some((<$target>::BITS - 1 - self.leading_zeros()) as $target)
+                }
+            }
+            #[inline]
+            fn is_power_of_two(self) -> bool {
+                <$target>::is_power_of_two(self)
+            }
+            #[inline]
+            fn next_power_of_two(self) -> $target {
+                <$target>::next_power_of_two(self)
             }
             #[inline(always)]
             fn divide_round_up(self, divisor: $target) -> $target {
                 assert!(divisor != 0, "Division by zero");
                 assert!(divisor != Self::MIN, "Division underflow");
-                unsafe {
-                    (self + divisor.uncheceked_sub(1)).unchecked_div(divisor)
-                }
+                /*
+                 * Adding `divisor - 1` before dividing could overflow,
+                 * so round up based on the remainder instead.
+                 * Computing both only takes a single division instruction.
+                 */
+                let quotient = self / divisor;
+                if self % divisor != 0 { quotient + 1 } else { quotient }
             }
         })*
     };
 }
-impl_prim_int!(u32, u64, usize);
\ No newline at end of file
+impl_prim_int!(u32, u64, usize);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ceil_log2() {
+        let expected = [0, 1, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 4, 4, 4, 4, 5];
+        for (value, &log) in (1..=17u64).zip(&expected) {
+            assert_eq!(value.ceil_log2(), log, "ceil_log2({})", value);
+            assert_eq!((value as u32).ceil_log2(), log as u32);
+            assert_eq!((value as usize).checked_ceil_log2(), Some(log as usize));
+        }
+        assert_eq!(0u64.ceil_log2(), 0);
+        assert_eq!(0u64.checked_ceil_log2(), None);
+        assert_eq!(u64::MAX.ceil_log2(), 64);
+    }
+    #[test]
+    fn log2_table() {
+        for value in 0..=64u64 {
+            // The smallest power of two at least `value`, found the slow way
+            let ceil = (0..64).find(|&bits| 1u64 << bits >= value).unwrap();
+            let floor = (0..64).rev().find(|&bits| 1u64 << bits <= value);
+            assert_eq!(value.checked_ceil_log2(), if value == 0 { None } else { Some(ceil) }, "{}", value);
+            assert_eq!(value.ceil_log2(), ceil, "{}", value);
+            assert_eq!(value.checked_floor_log2(), floor, "{}", value);
+            assert_eq!(ArithUtil::is_power_of_two(value), value != 0 && value == 1 << ceil, "{}", value);
+            assert_eq!(ArithUtil::next_power_of_two(value), 1 << ceil, "{}", value);
+        }
+    }
+}
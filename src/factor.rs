@@ -0,0 +1,265 @@
+//! Integer factorization, and the multiplicative orders built on it
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::memory::{WordArray, Word};
+use crate::uint::{UnsignedInteger, alloc_failure};
+
+/// Trial division finds every prime factor below this,
+/// before switching to Pollard's rho algorithm
+const TRIAL_DIVISION_BOUND: u64 = 1 << 12;
+
+/// The witnesses for the Miller-Rabin tests while factoring
+///
+/// Together, these prove primality below `3.3 * 10**24`.
+/// Past that, a composite passing all of them is astronomically unlikely.
+const PRIME_WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+impl<A: WordArray> UnsignedInteger<A> {
+    /// The multiplicative order of this integer modulo `modulus`,
+    /// the smallest `k > 0` where `self**k ≡ 1 (mod modulus)`
+    ///
+    /// Returns `None` if this integer and the modulus aren't coprime,
+    /// since then no power is ever one. This includes a zero modulus.
+    /// Modulo one, everything has order one.
+    ///
+    /// ## Performance
+    /// The order divides Euler's totient of the modulus,
+    /// so this factors the totient and divides out each prime factor
+    /// for as long as the power stays one.
+    /// Factoring the totient means factoring the modulus,
+    /// and `p - 1` for each of its prime factors `p`.
+    /// For a prime modulus, that's just factoring `modulus - 1`.
+    ///
+    /// Factoring uses trial division and Pollard's rho algorithm,
+    /// taking time roughly proportional to the square root
+    /// of the second largest prime factor of each of those.
+    /// That's fast for typical moduli (and for safe primes),
+    /// but hopeless once two factors have more than about 25 digits.
+    ///
+    /// Errors if allocating space fails
+    pub fn multiplicative_order(&self, modulus: &Self) -> Result<Option<Self>, A::AllocErr> {
+        if modulus.is_empty() || !self.is_coprime(modulus)? {
+            return Ok(None);
+        }
+        // Since `phi(p**e) = p**(e - 1) * (p - 1)`, factoring the modulus gives the totient
+        let mut totient_factors = Vec::new();
+        for (mut prime, exponent) in modulus.factorize()? {
+            if exponent > 1 {
+                add_factor(&mut totient_factors, prime.clone_with_capacity(0)?, exponent - 1);
+            }
+            prime.unchecked_sub_u64(1);
+            for (factor, exponent) in prime.factorize()? {
+                add_factor(&mut totient_factors, factor, exponent);
+            }
+        }
+        let mut order = self.with_capacity_like(1)?;
+        order.set_one()?;
+        for (prime, exponent) in &totient_factors {
+            order = UnsignedInteger::mul(&order, &prime.pow(*exponent)?)?;
+        }
+        for (prime, exponent) in &totient_factors {
+            for _ in 0..*exponent {
+                let (candidate, _) = order.div_rem(prime)
+                    .map_err(|cause| alloc_failure(cause.into()))?;
+                if !self.pow_mod(&candidate, modulus).map_err(alloc_failure)?.eq_u64(1) {
+                    break;
+                }
+                order = candidate;
+            }
+        }
+        Ok(Some(order))
+    }
+    /// The prime factorization of this integer,
+    /// as pairs of distinct primes and their multiplicities
+    ///
+    /// The primes aren't in any particular order.
+    /// Zero and one have no prime factors.
+    pub(crate) fn factorize(&self) -> Result<Vec<(Self, u32)>, A::AllocErr> {
+        let mut factors = Vec::new();
+        let mut remaining = self.clone_with_capacity(0)?;
+        if remaining.bit_len() <= 1 {
+            return Ok(factors);
+        }
+        // Trial division by two and every odd number catches all the small primes
+        let mut divisor = 2;
+        while divisor < TRIAL_DIVISION_BOUND && remaining.cmp_u64(divisor * divisor) != Ordering::Less {
+            let mut count = 0;
+            while rem_small(remaining.words(), divisor) == 0 {
+                // Can't fail, since the divisor is nonzero and division never allocates
+                remaining.div_rem_u64(divisor).unwrap();
+                count += 1;
+            }
+            if count > 0 {
+                let mut prime = self.with_capacity_like(1)?;
+                prime.set(divisor)?;
+                factors.push((prime, count));
+            }
+            divisor += if divisor == 2 { 1 } else { 2 };
+        }
+        if remaining.eq_u64(1) {
+            return Ok(factors);
+        }
+        if remaining.cmp_u64(divisor * divisor) == Ordering::Less {
+            // Without any factor up to its square root, it must be prime
+            factors.push((remaining, 1));
+            return Ok(factors);
+        }
+        let mut pending = Vec::new();
+        pending.push(remaining);
+        while let Some(value) = pending.pop() {
+            if value.is_prime_for_factoring()? {
+                add_factor(&mut factors, value, 1);
+                continue;
+            }
+            let factor = value.pollard_rho()?;
+            let (cofactor, _) = value.div_rem(&factor)
+                .map_err(|cause| alloc_failure(cause.into()))?;
+            pending.push(factor);
+            pending.push(cofactor);
+        }
+        Ok(factors)
+    }
+    /// Check if an integer without any small factors is prime,
+    /// using the Miller-Rabin test with each of the [PRIME_WITNESSES]
+    fn is_prime_for_factoring(&self) -> Result<bool, A::AllocErr> {
+        debug_assert!(self.cmp_u64(TRIAL_DIVISION_BOUND) == Ordering::Greater && self.is_odd());
+        let (n_minus_one, d, s) = self.miller_rabin_decompose()?;
+        let mut witness = self.with_capacity_like(1)?;
+        for &value in &PRIME_WITNESSES {
+            witness.set(value)?;
+            if !self.miller_rabin_round(&witness, &n_minus_one, &d, s)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+    /// Find a nontrivial factor of this odd composite integer,
+    /// using Pollard's rho algorithm
+    ///
+    /// Iterating `x**2 + c` modulo this integer must eventually cycle
+    /// modulo each of its prime factors, which Floyd's cycle detection notices
+    /// as a difference sharing a factor with this integer.
+    /// If every factor cycles at once, this tries again with the next `c`.
+    fn pollard_rho(&self) -> Result<Self, A::AllocErr> {
+        let mut c = 1;
+        loop {
+            let mut tortoise = self.with_capacity_like(1)?;
+            tortoise.set(2)?;
+            let mut hare = tortoise.clone_with_capacity(0)?;
+            loop {
+                tortoise = self.rho_step(&tortoise, c)?;
+                hare = self.rho_step(&hare, c)?;
+                hare = self.rho_step(&hare, c)?;
+                let mut difference = hare.clone_with_capacity(0)?;
+                if difference < tortoise {
+                    difference = tortoise.clone_with_capacity(0)?;
+                    difference.unchecked_sub(&hare);
+                } else {
+                    difference.unchecked_sub(&tortoise);
+                }
+                let divisor = difference.gcd(self)?;
+                if divisor.eq_u64(1) {
+                    continue;
+                }
+                if divisor != *self {
+                    return Ok(divisor);
+                }
+                break;
+            }
+            c += 1;
+        }
+    }
+    /// A single step of Pollard's rho iteration, `x**2 + c` modulo this integer
+    fn rho_step(&self, x: &Self, c: u64) -> Result<Self, A::AllocErr> {
+        let mut res = x.mul_mod(x, self).map_err(|cause| alloc_failure(cause.into()))?;
+        res.add_u64(c)?;
+        // The constant is tiny compared to this integer, so one subtraction reduces it
+        if res >= *self {
+            res.unchecked_sub(self);
+        }
+        Ok(res)
+    }
+}
+
+/// Add a prime factor to a factorization,
+/// combining it with an existing entry for the same prime
+fn add_factor<A: WordArray>(factors: &mut Vec<(UnsignedInteger<A>, u32)>, prime: UnsignedInteger<A>, exponent: u32) {
+    match factors.iter_mut().find(|(existing, _)| *existing == prime) {
+        Some((_, existing_exponent)) => *existing_exponent += exponent,
+        None => factors.push((prime, exponent))
+    }
+}
+
+/// The remainder of dividing the specified words by a single word,
+/// without modifying them
+fn rem_small(words: &[Word], divisor: u64) -> u64 {
+    let mut remainder = 0u64;
+    for word in words.iter().rev() {
+        let wide = ((remainder as u128) << 64) | word.0 as u128;
+        remainder = (wide % divisor as u128) as u64;
+    }
+    remainder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+
+    fn small(value: u64) -> UnsignedInteger {
+        value.into()
+    }
+    fn order(value: u64, modulus: u64) -> Option<u64> {
+        small(value).multiplicative_order(&small(modulus)).unwrap()
+            .map(|order| u64::try_from(&order).unwrap())
+    }
+    fn sorted_factors(value: &UnsignedInteger) -> Vec<(UnsignedInteger, u32)> {
+        let mut factors = value.factorize().unwrap();
+        factors.sort_by(|(left, _), (right, _)| left.cmp(right));
+        factors
+    }
+
+    #[test]
+    fn orders_match_brute_force() {
+        for modulus in 1..64u64 {
+            for value in 0..modulus {
+                let expected = (1..=modulus)
+                    .find(|&k| small(value).pow_mod(&small(k), &small(modulus)).unwrap().eq_u64(1 % modulus));
+                let expected = if small(value).gcd(&small(modulus)).unwrap().eq_u64(1) { expected } else { None };
+                assert_eq!(order(value, modulus), expected, "{} mod {}", value, modulus);
+            }
+        }
+        assert_eq!(order(5, 0), None);
+    }
+    #[test]
+    fn orders_of_large_moduli() {
+        // `2**61 - 1` is a Mersenne prime, so two has order 61
+        assert_eq!(order(2, (1 << 61) - 1), Some(61));
+        // Three is a cubic residue, with each prime factor of the order needed
+        let modulus = small((1 << 61) - 1);
+        let order_of_three = small(3).multiplicative_order(&modulus).unwrap().unwrap();
+        assert_eq!(order_of_three, small(((1 << 61) - 2) / 9));
+        assert!(small(3).pow_mod(&order_of_three, &modulus).unwrap().eq_u64(1));
+        for &prime in &[2, 5, 7, 11, 13, 31, 41, 61, 151, 331, 1321] {
+            let (smaller, _) = order_of_three.div_rem(&small(prime)).unwrap();
+            assert!(!small(3).pow_mod(&smaller, &modulus).unwrap().eq_u64(1));
+        }
+        // A product of two primes past trial division needs Pollard's rho
+        let (p, q) = (1_000_003u64, 1_000_033u64);
+        let order_modulo_product = order(2, p * q).unwrap();
+        let lcm = |a: u64, b: u64| a / small(a).gcd(&small(b)).unwrap().words()[0].0 * b;
+        assert_eq!(order_modulo_product, lcm(order(2, p).unwrap(), order(2, q).unwrap()));
+    }
+    #[test]
+    fn factorize() {
+        assert!(sorted_factors(&UnsignedInteger::ZERO).is_empty());
+        assert!(sorted_factors(&small(1)).is_empty());
+        assert_eq!(sorted_factors(&small(360)), vec![(small(2), 3), (small(3), 2), (small(5), 1)]);
+        assert_eq!(sorted_factors(&small(10_007 * 10_007)), vec![(small(10_007), 2)]);
+        let value = UnsignedInteger::from(1_000_003u128 * 1_000_033 * 4_294_967_311 * 12);
+        assert_eq!(sorted_factors(&value), vec![
+            (small(2), 2), (small(3), 1), (small(1_000_003), 1), (small(1_000_033), 1), (small(4_294_967_311), 1)
+        ]);
+    }
+}
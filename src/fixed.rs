@@ -0,0 +1,252 @@
+//! Fixed-width unsigned integers
+//!
+//! These are [UnsignedInteger]s backed by an [InlineWordArray],
+//! so they never touch the heap and can never grow past `N` words.
+//!
+//! ## Overflow
+//! The general operations on [UnsignedInteger] treat the width as a capacity,
+//! erroring with [CapacityExceeded](crate::memory::CapacityExceeded) whenever a result doesn't fit:
+//!
+//! - [add](UnsignedInteger::add), [checked_add](UnsignedInteger::checked_add),
+//!   [add_u64](UnsignedInteger::add_u64) and [mul_u64](UnsignedInteger::mul_u64)
+//!   only fail if the result actually needs more than `N` words.
+//! - [shl_assign](UnsignedInteger::shl_assign) fails if any set bit would be shifted out.
+//! - [mul](UnsignedInteger::mul) and [mul_into](UnsignedInteger::mul_into)
+//!   fail whenever the operands have more than `N` words between them,
+//!   *even if the product would fit*.
+//! - Subtraction, division and right shifts never overflow.
+//! - [pow_mod](UnsignedInteger::pow_mod) works with any odd modulus,
+//!   but an even modulus can only use half of the width.
+//!
+//! The methods in this module instead wrap around modulo `2**(64 * N)`,
+//! like the primitive integer types.
+use crate::memory::{InlineWordArray, Word};
+use crate::uint::{UnsignedInteger, mul_add_word_assign, sub_words_assign};
+
+/// A 128-bit unsigned integer
+pub type U128 = UnsignedInteger<InlineWordArray<2>>;
+/// A 256-bit unsigned integer
+pub type U256 = UnsignedInteger<InlineWordArray<4>>;
+/// A 512-bit unsigned integer
+pub type U512 = UnsignedInteger<InlineWordArray<8>>;
+/// A 1024-bit unsigned integer
+pub type U1024 = UnsignedInteger<InlineWordArray<16>>;
+
+impl<const N: usize> UnsignedInteger<InlineWordArray<N>> {
+    /// The largest value that fits in `N` words, `2**(64 * N) - 1`
+    pub const MAX: Self = UnsignedInteger {
+        words: InlineWordArray::from_array([Word(u64::MAX); N], N)
+    };
+    /// Create an integer from its little-endian words
+    ///
+    /// Unlike [UnsignedInteger::from_word_array], this is a `const fn`
+    /// and always gives a normalized integer.
+    pub const fn from_le_words(words: [u64; N]) -> Self {
+        let mut array = [Word(0); N];
+        let mut len = 0;
+        let mut index = 0;
+        while index < N {
+            array[index] = Word(words[index]);
+            if words[index] != 0 {
+                len = index + 1;
+            }
+            index += 1;
+        }
+        UnsignedInteger { words: InlineWordArray::from_array(array, len) }
+    }
+    /// Create an integer from a single [u64]
+    ///
+    /// This is the `const fn` equivalent of [UnsignedInteger::set].
+    ///
+    /// ## Panics
+    /// If the value is nonzero and `N` is zero
+    pub const fn from_u64_const(val: u64) -> Self {
+        let mut array = [Word(0); N];
+        if val == 0 {
+            return UnsignedInteger { words: InlineWordArray::from_array(array, 0) };
+        }
+        assert!(N > 0, "Nonzero value doesn't fit in zero words");
+        array[0] = Word(val);
+        UnsignedInteger { words: InlineWordArray::from_array(array, 1) }
+    }
+    /// The words of this integer, padded with zeros to the full width
+    #[inline]
+    fn padded_words(&self) -> [Word; N] {
+        let mut words = [Word(0); N];
+        words[..self.words().len()].copy_from_slice(self.words());
+        words
+    }
+    #[inline]
+    fn from_padded_words(words: [Word; N]) -> Self {
+        let mut res = UnsignedInteger { words: InlineWordArray::from_array(words, N) };
+        res.trim();
+        res
+    }
+    /// Add the specified integer, wrapping around at the width
+    /// and returning whether the sum overflowed
+    ///
+    /// This mirrors [u64::overflowing_add].
+    pub fn overflowing_add(&self, other: &Self) -> (Self, bool) {
+        let mut words = self.padded_words();
+        let mut carry = false;
+        for (word, &addend) in words.iter_mut().zip(other.words()) {
+            let (sum, first_carry) = word.0.overflowing_add(addend.0);
+            let (sum, second_carry) = sum.overflowing_add(carry as u64);
+            word.0 = sum;
+            carry = first_carry || second_carry;
+        }
+        if carry {
+            // Propagate through the words past the end of `other`
+            for word in &mut words[other.words().len()..] {
+                let (sum, overflowed) = word.0.overflowing_add(1);
+                word.0 = sum;
+                if !overflowed {
+                    carry = false;
+                    break;
+                }
+            }
+        }
+        (Self::from_padded_words(words), carry)
+    }
+    /// Add the specified integer, wrapping around at the width
+    ///
+    /// This mirrors [u64::wrapping_add].
+    #[inline]
+    pub fn wrapping_add(&self, other: &Self) -> Self {
+        self.overflowing_add(other).0
+    }
+    /// Subtract the specified integer, wrapping around at zero
+    /// and returning whether the subtraction underflowed
+    ///
+    /// On underflow, the result is `2**(64 * N) - (other - self)`,
+    /// just like [u64::overflowing_sub].
+    pub fn overflowing_sub(&self, other: &Self) -> (Self, bool) {
+        let mut words = self.padded_words();
+        let borrow = sub_words_assign(&mut words, other.words());
+        (Self::from_padded_words(words), borrow)
+    }
+    /// Subtract the specified integer, wrapping around at zero
+    ///
+    /// This mirrors [u64::wrapping_sub].
+    #[inline]
+    pub fn wrapping_sub(&self, other: &Self) -> Self {
+        self.overflowing_sub(other).0
+    }
+    /// Multiply by the specified integer, keeping only the low `N` words
+    /// and returning whether any higher bits were discarded
+    ///
+    /// Unlike [UnsignedInteger::mul], this never fails
+    /// just because the operands are long.
+    /// Only the product itself is checked for overflow.
+    pub fn overflowing_mul(&self, other: &Self) -> (Self, bool) {
+        let (left, right) = (self.words(), other.words());
+        let mut product = [Word(0); N];
+        let mut overflowed = false;
+        for (offset, &multiplier) in right.iter().enumerate() {
+            if multiplier.0 == 0 {
+                continue;
+            }
+            /*
+             * Words of `left` at or past `N - offset` land entirely past the width.
+             * Since `left` is normalized, dropping any of them drops its nonzero top word.
+             */
+            let kept = left.len().min(N - offset);
+            overflowed |= kept < left.len();
+            let carry = unsafe { mul_add_word_assign(&mut product[offset..], &left[..kept], multiplier) };
+            if offset + kept < N {
+                // Earlier rows never reach this high, so the word is still zero
+                product[offset + kept] = carry;
+            } else {
+                overflowed |= carry.0 != 0;
+            }
+        }
+        (Self::from_padded_words(product), overflowed)
+    }
+    /// Multiply by the specified integer, keeping only the low `N` words
+    ///
+    /// This mirrors [u64::wrapping_mul].
+    #[inline]
+    pub fn wrapping_mul(&self, other: &Self) -> Self {
+        self.overflowing_mul(other).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random(rng: &mut SmallRng) -> U256 {
+        let len = rng.gen_range(0..=4);
+        let mut words = [0u64; 4];
+        for word in &mut words[..len] {
+            *word = rng.gen();
+        }
+        U256::from_le_words(words)
+    }
+    /// The exact result of an operation, truncated to 256 bits
+    fn truncated(exact: &UnsignedInteger) -> (U256, bool) {
+        let mut words = [0u64; 4];
+        for (word, exact) in words.iter_mut().zip(exact.words()) {
+            *word = exact.0;
+        }
+        (U256::from_le_words(words), exact.words().len() > 4)
+    }
+    fn widen(val: &U256) -> UnsignedInteger {
+        UnsignedInteger::from_word_array(val.words().to_vec())
+    }
+
+    #[test]
+    fn const_constructors() {
+        const ONE: U256 = U256::from_u64_const(1);
+        const PADDED: U256 = U256::from_le_words([7, 0, 0, 0]);
+        const HIGH: U256 = U256::from_le_words([0, 0, 1, 0]);
+        assert_eq!(ONE.words(), &[Word(1)]);
+        assert_eq!(PADDED.words().len(), 1);
+        assert_eq!(PADDED.check_invariants(), Ok(()));
+        assert_eq!(HIGH.bit_len(), 129);
+        assert_eq!(U256::from_u64_const(0), U256::ZERO);
+        assert_eq!(U256::MAX.bit_len(), 256);
+        assert_eq!(U128::MAX.bit_len(), 128);
+    }
+    #[test]
+    fn wrapping_at_the_edges() {
+        let one = U256::from_u64_const(1);
+        assert_eq!(U256::MAX.overflowing_add(&one), (U256::ZERO, true));
+        assert_eq!(U256::ZERO.overflowing_sub(&one), (U256::MAX, true));
+        assert_eq!(U256::MAX.overflowing_mul(&U256::MAX), (one.clone(), true));
+        assert_eq!(U256::MAX.overflowing_mul(&one), (U256::MAX, false));
+        // 2**128 * 2**128 loses every bit
+        let half = U256::from_le_words([0, 0, 1, 0]);
+        assert_eq!(half.overflowing_mul(&half), (U256::ZERO, true));
+        // The general multiplication refuses even though 2**192 * 2 fits
+        let high = U256::from_le_words([0, 0, 0, 1]);
+        let two = U256::from_le_words([2, 0, 0, 0]);
+        assert!(UnsignedInteger::mul(&high, &two).is_err());
+        assert_eq!(high.overflowing_mul(&two), (U256::from_le_words([0, 0, 0, 2]), false));
+    }
+    #[test]
+    fn full_width_u64_ops() {
+        let mut val = U256::MAX;
+        val.sub_u64(1).unwrap();
+        val.div_rem_u64(2).unwrap();
+        // A full array can still be doubled when the product fits
+        val.mul_u64(2).unwrap();
+        assert_eq!(val, U256::MAX.wrapping_sub(&U256::from_u64_const(1)));
+        assert!(U256::MAX.clone().mul_u64(2).is_err());
+    }
+    #[test]
+    fn matches_exact_arithmetic() {
+        let mut rng = SmallRng::seed_from_u64(220);
+        for _ in 0..1000 {
+            let (left, right) = (random(&mut rng), random(&mut rng));
+            let (wide_left, wide_right) = (widen(&left), widen(&right));
+            assert_eq!(left.overflowing_add(&right), truncated(&wide_left.checked_add(&wide_right).unwrap()));
+            assert_eq!(left.overflowing_mul(&right), truncated(&UnsignedInteger::mul(&wide_left, &wide_right).unwrap()));
+            let (difference, borrow) = left.overflowing_sub(&right);
+            assert_eq!(borrow, left < right);
+            assert_eq!(difference.wrapping_add(&right), left);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! Signed integers
+use core::ops::{Add, Sub, Neg};
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Formatter};
+
+use crate::memory::WordArray;
+use crate::uint::{UnsignedInteger, Magnitude};
+#[cfg(feature = "alloc")]
+use crate::memory::Word;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// The sign of a signed integer
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum Sign {
+    /// Less than zero
+    Negative,
+    /// Exactly zero
+    Zero,
+    /// Greater than zero
+    Positive
+}
+impl Sign {
+    /// The opposite sign, where zero is its own opposite
+    #[inline]
+    pub fn flip(self) -> Sign {
+        match self {
+            Sign::Negative => Sign::Positive,
+            Sign::Zero => Sign::Zero,
+            Sign::Positive => Sign::Negative
+        }
+    }
+}
+impl Neg for Sign {
+    type Output = Sign;
+
+    #[inline]
+    fn neg(self) -> Sign {
+        self.flip()
+    }
+}
+
+/// A signed integer,
+/// stored as a sign and an unsigned magnitude
+///
+/// Zero always has [Sign::Zero], so each value
+/// has a unique representation.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct SignedInteger<A: WordArray = Vec<Word>> {
+    sign: Sign,
+    magnitude: UnsignedInteger<A>
+}
+/// A signed integer,
+/// stored as a sign and an unsigned magnitude
+///
+/// Zero always has [Sign::Zero], so each value
+/// has a unique representation.
+#[cfg(not(feature = "alloc"))]
+#[derive(Clone)]
+pub struct SignedInteger<A: WordArray> {
+    sign: Sign,
+    magnitude: UnsignedInteger<A>
+}
+impl<A: WordArray> SignedInteger<A> {
+    /// Zero
+    pub const ZERO: Self = SignedInteger { sign: Sign::Zero, magnitude: UnsignedInteger::ZERO };
+    /// Create a signed integer from a sign and a magnitude
+    ///
+    /// If the magnitude is zero, the sign is ignored.
+    ///
+    /// ## Panics
+    /// If the sign is [Sign::Zero] but the magnitude isn't
+    #[inline]
+    pub fn from_sign_magnitude(sign: Sign, magnitude: UnsignedInteger<A>) -> Self {
+        if magnitude.is_empty() {
+            SignedInteger::ZERO
+        } else {
+            assert_ne!(sign, Sign::Zero, "Nonzero magnitude with zero sign");
+            SignedInteger { sign, magnitude }
+        }
+    }
+    /// The sign of this integer
+    #[inline]
+    pub fn sign(&self) -> Sign {
+        self.sign
+    }
+    /// The absolute value of this integer, as an unsigned integer
+    #[inline]
+    pub fn magnitude(&self) -> &UnsignedInteger<A> {
+        &self.magnitude
+    }
+    /// Consume this integer, returning its absolute value as an unsigned integer
+    #[inline]
+    pub fn into_magnitude(self) -> UnsignedInteger<A> {
+        self.magnitude
+    }
+    /// Check if this integer is less than zero
+    #[inline]
+    pub fn is_negative(&self) -> bool {
+        self.sign == Sign::Negative
+    }
+    /// The absolute value of this integer
+    ///
+    /// This never allocates, since only the sign changes.
+    #[inline]
+    pub fn abs(mut self) -> Self {
+        if self.sign == Sign::Negative {
+            self.sign = Sign::Positive;
+        }
+        self
+    }
+    /// Negative one, zero, or one depending on the sign of this integer
+    ///
+    /// Errors if allocating space fails
+    pub fn signum(&self) -> Result<Self, A::AllocErr> {
+        let mut magnitude = self.magnitude.with_capacity_like(0)?;
+        if self.sign != Sign::Zero {
+            magnitude.set_one()?;
+        }
+        Ok(SignedInteger { sign: self.sign, magnitude })
+    }
+    /// Add the specified integer to this integer
+    ///
+    /// Errors if allocating space fails,
+    /// in which case this integer is left unchanged.
+    #[inline]
+    pub fn add(&mut self, other: &Self) -> Result<(), A::AllocErr> {
+        self.add_signed(other.sign, &other.magnitude)
+    }
+    /// Subtract the specified integer from this integer
+    ///
+    /// Unlike unsigned subtraction, this can never underflow.
+    ///
+    /// Errors if allocating space fails,
+    /// in which case this integer is left unchanged.
+    #[inline]
+    pub fn sub(&mut self, other: &Self) -> Result<(), A::AllocErr> {
+        self.add_signed(other.sign.flip(), &other.magnitude)
+    }
+    /// Add an integer with the specified sign and magnitude
+    fn add_signed(&mut self, other_sign: Sign, other_magnitude: &UnsignedInteger<A>) -> Result<(), A::AllocErr> {
+        match (self.sign, other_sign) {
+            (_, Sign::Zero) => Ok(()),
+            (Sign::Zero, _) => {
+                self.magnitude = other_magnitude.clone_with_capacity(0)?;
+                self.sign = other_sign;
+                Ok(())
+            },
+            (sign, other_sign) if sign == other_sign => {
+                // Same signs, so the magnitudes add up
+                UnsignedInteger::add(&mut self.magnitude, other_magnitude)
+            },
+            _ => {
+                // Opposite signs, so subtract the smaller magnitude from the larger
+                match self.magnitude.cmp_magnitude(other_magnitude) {
+                    Ordering::Greater => {
+                        self.magnitude.unchecked_sub(other_magnitude);
+                    },
+                    Ordering::Equal => {
+                        *self = SignedInteger::ZERO;
+                    },
+                    Ordering::Less => {
+                        let mut magnitude = other_magnitude.clone_with_capacity(0)?;
+                        magnitude.unchecked_sub(&self.magnitude);
+                        self.magnitude = magnitude;
+                        self.sign = other_sign;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+impl<A: WordArray> From<UnsignedInteger<A>> for SignedInteger<A> {
+    #[inline]
+    fn from(magnitude: UnsignedInteger<A>) -> Self {
+        SignedInteger::from_sign_magnitude(Sign::Positive, magnitude)
+    }
+}
+impl<A: WordArray> Debug for SignedInteger<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.sign == Sign::Negative { "SignedInteger(-0x" } else { "SignedInteger(0x" })?;
+        let mut words = self.magnitude.words_be();
+        match words.next() {
+            Some(top) => write!(f, "{:x}", top.0)?,
+            None => f.write_str("0")?
+        }
+        for word in words {
+            write!(f, "{:016x}", word.0)?;
+        }
+        f.write_str(")")
+    }
+}
+impl<A: WordArray> PartialEq for SignedInteger<A> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        // Zero always has the same sign, so this is well-defined
+        self.sign == other.sign && self.magnitude == other.magnitude
+    }
+}
+impl<A: WordArray> Eq for SignedInteger<A> {}
+impl<A: WordArray> Magnitude for SignedInteger<A> {
+    type Array = A;
+    #[inline]
+    fn magnitude(&self) -> &UnsignedInteger<A> {
+        &self.magnitude
+    }
+}
+impl<A: WordArray> PartialOrd for SignedInteger<A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<A: WordArray> Ord for SignedInteger<A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sign.cmp(&other.sign).then_with(|| match self.sign {
+            Sign::Negative => other.magnitude.cmp(&self.magnitude),
+            Sign::Zero => Ordering::Equal,
+            Sign::Positive => self.magnitude.cmp(&other.magnitude)
+        })
+    }
+}
+impl<A: WordArray> Neg for SignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(mut self) -> Self::Output {
+        self.sign = self.sign.flip();
+        self
+    }
+}
+impl<A: WordArray> Add for SignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn add(mut self, rhs: Self) -> Self::Output {
+        SignedInteger::add(&mut self, &rhs).unwrap();
+        self
+    }
+}
+impl<A: WordArray> Sub for SignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        SignedInteger::sub(&mut self, &rhs).unwrap();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signed(value: i64) -> SignedInteger<Vec<Word>> {
+        let sign = match value.signum() {
+            -1 => Sign::Negative,
+            0 => Sign::Zero,
+            _ => Sign::Positive
+        };
+        SignedInteger::from_sign_magnitude(sign, UnsignedInteger::from(value.unsigned_abs()))
+    }
+
+    #[test]
+    fn add_and_sub() {
+        assert_eq!(signed(-3) + signed(5), signed(2));
+        assert_eq!(signed(3) + signed(-5), signed(-2));
+        assert_eq!(signed(-3) + signed(-5), signed(-8));
+        // Cancelling out always gives the zero sign
+        let zero = signed(-3) + signed(3);
+        assert_eq!(zero.sign(), Sign::Zero);
+        assert_eq!(zero, SignedInteger::ZERO);
+        assert_eq!(signed(3) - signed(5), signed(-2));
+        assert_eq!(signed(-3) - signed(-3), signed(0));
+        for left in -20..=20 {
+            for right in -20..=20 {
+                assert_eq!(signed(left) + signed(right), signed(left + right), "{} + {}", left, right);
+                assert_eq!(signed(left) - signed(right), signed(left - right), "{} - {}", left, right);
+            }
+        }
+    }
+    #[test]
+    fn neg_abs_signum() {
+        assert_eq!(-signed(7), signed(-7));
+        assert_eq!((-signed(0)).sign(), Sign::Zero);
+        assert_eq!(signed(-7).abs(), signed(7));
+        assert_eq!(signed(-7).signum().unwrap(), signed(-1));
+        assert_eq!(signed(0).signum().unwrap(), signed(0));
+        assert_eq!(signed(42).signum().unwrap(), signed(1));
+        assert!(signed(-1) < signed(0) && signed(0) < signed(1) && signed(-5) < signed(-4));
+    }
+}
@@ -1,15 +1,43 @@
 #![feature(
-    unchecked_math, // Gotta go fast
     never_type, // Cleaner than `enum Empty {}`
 )]
 #![deny(missing_docs)]
 #![deny(clippy::missing_safety_doc)]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! A fast, liberally licensed multiple precision
 //! arithmetic library.
 //!
-//! Only implements integer arithmetic.
+//! Only implements integer arithmetic,
+//! with signed integers wrapping the unsigned ones.
+//!
+//! Without the default `std` feature, this crate is `no_std`.
+//! The `alloc` feature still provides the heap allocated [WordArray](memory::WordArray)s
+//! and conversions to strings. Otherwise, only fixed-capacity arrays
+//! like [InlineWordArray](memory::InlineWordArray) are available.
+//!
+//! The optional `serde` feature implements serialization,
+//! using a decimal string for human-readable formats
+//! and little-endian bytes for binary ones.
+//! The optional `subtle` feature adds constant-time comparison,
+//! and the `bump` feature adds arrays allocated from a bumpalo arena.
+//! The optional `rand` feature generates random integers
+//! and tests for probable primes.
+//! The optional `rayon` feature formats huge integers in parallel.
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 pub mod arith_utils;
 pub mod uint;
+pub mod int;
+pub mod montgomery;
 pub mod memory;
-mod string;
\ No newline at end of file
+pub mod fixed;
+mod string;
+#[cfg(feature = "alloc")]
+mod factor;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "subtle")]
+mod subtle_impls;
+#[cfg(feature = "rand")]
+mod rand_impls;
\ No newline at end of file
@@ -8,39 +8,132 @@
 //! This is handy for users who need special
 //! FFI compatibility or are writing
 //! a garbage collected language implementation.
-use std::fmt::Debug;
+use core::fmt::{self, Debug, Formatter};
+#[cfg(feature = "alloc")]
+use alloc::collections::TryReserveError;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use thiserror::Error;
 
 /// A single word in an arbitrary precision
 /// arithmetic.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct Word(pub u64);
 impl Word {
+    /// The number of bits in a word
     pub const BITS: u64 = 64;
 }
 
+/// The order of bytes or words in an external representation
+///
+/// Taken by conversions to and from external formats,
+/// whose `_be` and `_le` variants are just shorthand for each value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Endian {
+    /// Most significant first
+    Big,
+    /// Least significant first
+    ///
+    /// This is the order of words in a [WordArray]
+    Little
+}
+
 /// A trait for allocation errors
-pub trait IAllocError: std::error::Error {
+pub trait IAllocError: core::error::Error {
     /// Create an error indicating that capacity arithmetic overflowed
     fn capacity_arithmetic_overflow() -> Self;
+    /// Check if this error was created by [IAllocError::capacity_arithmetic_overflow],
+    /// as opposed to a genuine allocation failure
+    #[inline]
+    fn is_capacity_overflow(&self) -> bool {
+        false
+    }
+}
+
+/// An error indicating that an operation needed more words
+/// than the fixed capacity of the array allows
+#[derive(Error, Debug, Copy, Clone, Eq, PartialEq)]
+#[error("Needed {required} words, exceeding the fixed capacity")]
+pub struct CapacityExceeded {
+    /// The number of words that were needed
+    ///
+    /// This is `usize::MAX` if computing it overflowed.
+    pub required: usize
+}
+impl IAllocError for CapacityExceeded {
+    #[cold]
+    fn capacity_arithmetic_overflow() -> Self {
+        CapacityExceeded { required: usize::MAX }
+    }
+    #[inline]
+    fn is_capacity_overflow(&self) -> bool {
+        self.required == usize::MAX
+    }
 }
 
 /// An array of [Words](Word)
+///
+/// ## Safety
+/// The length and capacity must be accurate,
+/// since [WordArray::unchecked_push] relies on them
+/// and the integers built on top of this skip bounds checks.
 pub unsafe trait WordArray: AsRef<[Word]> + AsMut<[Word]> + Clone + Debug + Default {
+    /// An empty array, which doesn't need to allocate
     const EMPTY: Self;
     /// An error indicating that allocation failed
     type AllocErr: IAllocError;
     /// Pre-allocate the array with the specified capacity
     fn with_capacity(capacity: usize) -> Result<Self, Self::AllocErr>;
 
+    /// Pre-allocate an array with the specified capacity,
+    /// allocating from the same place as this array
+    ///
+    /// Operations that build a new integer from existing ones use this,
+    /// so arrays tied to a particular allocator (like [BumpWordArray])
+    /// can pass it along. By default, this is just [WordArray::with_capacity],
+    /// which is right for any array using a global allocator.
+    #[inline]
+    fn with_capacity_like(&self, capacity: usize) -> Result<Self, Self::AllocErr> {
+        Self::with_capacity(capacity)
+    }
+
     /// The length of the array
     fn len(&self) -> usize;
 
+    /// Check if the array is empty
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Empty the array, setting the length to zero
     fn clear(&mut self);
 
+    /// Shorten the array, keeping the first `len` words
+    ///
+    /// Has no effect if the array is already shorter than `len`.
+    fn truncate(&mut self, len: usize);
+
+    /// Release any excess capacity beyond the length of the array
+    ///
+    /// This is only a hint. By default it does nothing,
+    /// which is appropriate for arrays with a fixed capacity.
+    #[inline]
+    fn shrink_to_fit(&mut self) {}
+
     /// The capacity of the array
     fn capacity(&self) -> usize;
 
+    /// The approximate number of bytes of heap memory owned by the array
+    ///
+    /// By default, this assumes the entire capacity is heap allocated.
+    /// Arrays that store their words inline should override this to return zero.
+    #[inline]
+    fn heap_bytes(&self) -> usize {
+        self.capacity().saturating_mul(core::mem::size_of::<Word>())
+    }
+
     /// Ensure the array's capacity is at least the specified size
     ///
     /// Errors if allocation fails.
@@ -72,7 +165,12 @@ impl IAllocError for ! {
     fn capacity_arithmetic_overflow() -> Self {
         panic!("Capacity arithmetic overflow")
     }
+    #[inline]
+    fn is_capacity_overflow(&self) -> bool {
+        match *self {}
+    }
 }
+#[cfg(feature = "alloc")]
 unsafe impl WordArray for Vec<Word> {
     const EMPTY: Self = Vec::new();
     type AllocErr = !;
@@ -91,6 +189,16 @@ unsafe impl WordArray for Vec<Word> {
         self.clear();
     }
 
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.shrink_to_fit();
+    }
+
     #[inline]
     fn capacity(&self) -> usize {
         self.capacity()
@@ -104,9 +212,479 @@ unsafe impl WordArray for Vec<Word> {
 
     #[inline]
     unsafe fn unchecked_push(&mut self, word: Word) {
-        debug_assert!(self.len() + 1 <= self.capacity());
+        debug_assert!(self.len() < self.capacity());
         let end = self.as_mut_ptr().add(self.len());
         end.write(word);
         self.set_len(self.len().unchecked_add(1));
     }
-}
\ No newline at end of file
+}
+
+/// A [WordArray] storing up to `N` words inline,
+/// without ever touching the heap
+///
+/// This is intended for fixed-precision arithmetic,
+/// where the maximum size is known up front.
+/// For example, `UnsignedInteger<InlineWordArray<4>>` holds 256-bit values.
+/// Requesting more than `N` words fails with [CapacityExceeded].
+#[derive(Copy, Clone)]
+pub struct InlineWordArray<const N: usize> {
+    len: usize,
+    words: [Word; N]
+}
+impl<const N: usize> InlineWordArray<N> {
+    /// Create an array holding the first `len` of the specified words
+    ///
+    /// Any words past the length are ignored.
+    ///
+    /// ## Panics
+    /// If the length is greater than `N`
+    #[inline]
+    pub const fn from_array(words: [Word; N], len: usize) -> Self {
+        assert!(len <= N, "Length exceeds capacity");
+        InlineWordArray { len, words }
+    }
+}
+impl<const N: usize> AsRef<[Word]> for InlineWordArray<N> {
+    #[inline]
+    fn as_ref(&self) -> &[Word] {
+        &self.words[..self.len]
+    }
+}
+impl<const N: usize> AsMut<[Word]> for InlineWordArray<N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [Word] {
+        &mut self.words[..self.len]
+    }
+}
+impl<const N: usize> Debug for InlineWordArray<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_ref()).finish()
+    }
+}
+impl<const N: usize> Default for InlineWordArray<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+unsafe impl<const N: usize> WordArray for InlineWordArray<N> {
+    const EMPTY: Self = InlineWordArray { len: 0, words: [Word(0); N] };
+    type AllocErr = CapacityExceeded;
+    #[inline]
+    fn with_capacity(capacity: usize) -> Result<Self, CapacityExceeded> {
+        if capacity > N {
+            return Err(CapacityExceeded { required: capacity });
+        }
+        Ok(Self::EMPTY)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn heap_bytes(&self) -> usize {
+        0
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> Result<(), CapacityExceeded> {
+        let required = self.len.checked_add(additional)
+            .ok_or_else(CapacityExceeded::capacity_arithmetic_overflow)?;
+        if required > N {
+            return Err(CapacityExceeded { required });
+        }
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn unchecked_push(&mut self, word: Word) {
+        debug_assert!(self.len < N);
+        *self.words.get_unchecked_mut(self.len) = word;
+        self.len = self.len.unchecked_add(1);
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// An error allocating space for a [FallibleVec]
+#[derive(Error, Debug, Clone, Eq, PartialEq)]
+pub enum FallibleVecError {
+    /// Computing the needed capacity overflowed
+    #[error("Capacity arithmetic overflow")]
+    CapacityOverflow,
+    /// The allocator failed to reserve space
+    #[error("Allocation failed: {cause}")]
+    ReserveFailed {
+        /// The underlying error from the [Vec]
+        #[from]
+        cause: TryReserveError
+    }
+}
+#[cfg(feature = "alloc")]
+impl IAllocError for FallibleVecError {
+    #[cold]
+    fn capacity_arithmetic_overflow() -> Self {
+        FallibleVecError::CapacityOverflow
+    }
+    #[inline]
+    fn is_capacity_overflow(&self) -> bool {
+        matches!(self, FallibleVecError::CapacityOverflow)
+    }
+}
+
+/// A heap allocated [WordArray] that returns an error
+/// when allocation fails, instead of aborting the process
+///
+/// This is a wrapper around `Vec<Word>`,
+/// which uses [Vec::try_reserve] to grow.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct FallibleVec(Vec<Word>);
+#[cfg(feature = "alloc")]
+impl FallibleVec {
+    /// Unwrap the underlying vector of words
+    #[inline]
+    pub fn into_vec(self) -> Vec<Word> {
+        self.0
+    }
+}
+#[cfg(feature = "alloc")]
+impl From<Vec<Word>> for FallibleVec {
+    #[inline]
+    fn from(words: Vec<Word>) -> Self {
+        FallibleVec(words)
+    }
+}
+#[cfg(feature = "alloc")]
+impl AsRef<[Word]> for FallibleVec {
+    #[inline]
+    fn as_ref(&self) -> &[Word] {
+        &self.0
+    }
+}
+#[cfg(feature = "alloc")]
+impl AsMut<[Word]> for FallibleVec {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [Word] {
+        &mut self.0
+    }
+}
+#[cfg(feature = "alloc")]
+unsafe impl WordArray for FallibleVec {
+    const EMPTY: Self = FallibleVec(Vec::new());
+    type AllocErr = FallibleVecError;
+    #[inline]
+    fn with_capacity(capacity: usize) -> Result<Self, FallibleVecError> {
+        let mut words = Vec::new();
+        words.try_reserve_exact(capacity)?;
+        Ok(FallibleVec(words))
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
+    #[inline]
+    fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit();
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) -> Result<(), FallibleVecError> {
+        self.0.try_reserve(additional)?;
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn unchecked_push(&mut self, word: Word) {
+        WordArray::unchecked_push(&mut self.0, word);
+    }
+}
+
+/// An error allocating space for a [BumpWordArray]
+#[cfg(feature = "bump")]
+#[derive(Error, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BumpAllocError {
+    /// Computing the needed capacity overflowed
+    #[error("Capacity arithmetic overflow")]
+    CapacityOverflow,
+    /// The array has no arena to allocate from
+    ///
+    /// See the limitations of [BumpWordArray]
+    #[error("No arena to allocate from")]
+    NoArena,
+    /// The arena was exhausted
+    #[error("Bump arena exhausted")]
+    Exhausted
+}
+#[cfg(feature = "bump")]
+impl IAllocError for BumpAllocError {
+    #[cold]
+    fn capacity_arithmetic_overflow() -> Self {
+        BumpAllocError::CapacityOverflow
+    }
+    #[inline]
+    fn is_capacity_overflow(&self) -> bool {
+        matches!(self, BumpAllocError::CapacityOverflow)
+    }
+}
+
+/// A [WordArray] allocated from a [bumpalo::Bump] arena
+///
+/// This avoids a separate heap allocation for each integer,
+/// which is handy for language runtimes with many short-lived integers.
+/// Since an arena can't free individual allocations,
+/// growing the array abandons the old region until the whole arena is reset.
+///
+/// ## Limitations
+/// Only arrays created with [BumpWordArray::new_in] know their arena.
+/// Operations that build a new integer from existing ones (like `mul` and `div_rem`)
+/// allocate from the arena of their operands, through [WordArray::with_capacity_like].
+/// However, [WordArray::with_capacity] (and [WordArray::EMPTY]) have no way to receive one,
+/// so they can only create empty arrays that fail with [BumpAllocError::NoArena] when they grow.
+/// This affects constructors that don't start from an existing integer,
+/// like [from_bytes](crate::uint::UnsignedInteger::from_bytes) and parsing.
+/// Instead, start from an empty integer in the arena and fill it in place:
+///
+/// ```
+/// # use duckmp::memory::BumpWordArray;
+/// # use duckmp::uint::UnsignedInteger;
+/// let arena = bumpalo::Bump::new();
+/// let mut value = UnsignedInteger::from_word_array(BumpWordArray::new_in(&arena));
+/// value.set_from_str_radix("123456789012345678901234567890", 10).unwrap();
+/// let square = value.mul(&value).unwrap();
+/// assert_eq!(square.div_rem(&value).unwrap().0, value);
+/// ```
+#[cfg(feature = "bump")]
+pub struct BumpWordArray<'a> {
+    arena: Option<&'a bumpalo::Bump>,
+    len: usize,
+    /// The entire capacity, including unused words past the length
+    words: &'a mut [Word]
+}
+#[cfg(feature = "bump")]
+impl<'a> BumpWordArray<'a> {
+    /// Create an empty array that allocates from the specified arena
+    #[inline]
+    pub fn new_in(arena: &'a bumpalo::Bump) -> Self {
+        BumpWordArray { arena: Some(arena), len: 0, words: &mut [] }
+    }
+}
+#[cfg(feature = "bump")]
+impl Clone for BumpWordArray<'_> {
+    fn clone(&self) -> Self {
+        match self.arena {
+            Some(arena) => BumpWordArray {
+                arena: Some(arena),
+                len: self.len,
+                words: arena.alloc_slice_copy(self.as_ref())
+            },
+            None => BumpWordArray::EMPTY
+        }
+    }
+}
+#[cfg(feature = "bump")]
+impl Debug for BumpWordArray<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_ref()).finish()
+    }
+}
+#[cfg(feature = "bump")]
+impl Default for BumpWordArray<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+#[cfg(feature = "bump")]
+impl AsRef<[Word]> for BumpWordArray<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[Word] {
+        &self.words[..self.len]
+    }
+}
+#[cfg(feature = "bump")]
+impl AsMut<[Word]> for BumpWordArray<'_> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [Word] {
+        &mut self.words[..self.len]
+    }
+}
+#[cfg(feature = "bump")]
+unsafe impl WordArray for BumpWordArray<'_> {
+    const EMPTY: Self = BumpWordArray {
+        arena: None,
+        len: 0,
+        // Empty slices never access memory, so any aligned pointer is valid
+        words: unsafe { core::slice::from_raw_parts_mut(core::ptr::NonNull::dangling().as_ptr(), 0) }
+    };
+    type AllocErr = BumpAllocError;
+    #[inline]
+    fn with_capacity(capacity: usize) -> Result<Self, BumpAllocError> {
+        if capacity == 0 {
+            Ok(Self::EMPTY)
+        } else {
+            Err(BumpAllocError::NoArena)
+        }
+    }
+
+    fn with_capacity_like(&self, capacity: usize) -> Result<Self, BumpAllocError> {
+        let mut res = BumpWordArray { arena: self.arena, len: 0, words: &mut [] };
+        res.reserve(capacity)?;
+        Ok(res)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        // The words stay allocated until the arena is reset
+        self.len = 0;
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    #[inline]
+    fn capacity(&self) -> usize {
+        self.words.len()
+    }
+
+    #[inline]
+    fn heap_bytes(&self) -> usize {
+        // The arena owns the memory, not the array
+        0
+    }
+
+    fn reserve(&mut self, additional: usize) -> Result<(), BumpAllocError> {
+        let required = self.len.checked_add(additional)
+            .ok_or(BumpAllocError::CapacityOverflow)?;
+        if required <= self.words.len() {
+            return Ok(());
+        }
+        let arena = self.arena.ok_or(BumpAllocError::NoArena)?;
+        // Grow geometrically, so repeated pushes don't fill the arena with abandoned regions
+        let capacity = required.max(self.words.len().saturating_mul(2));
+        let words = arena.try_alloc_slice_fill_copy(capacity, Word(0))
+            .map_err(|_| BumpAllocError::Exhausted)?;
+        words[..self.len].copy_from_slice(&self.words[..self.len]);
+        self.words = words;
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn unchecked_push(&mut self, word: Word) {
+        debug_assert!(self.len < self.words.len());
+        *self.words.get_unchecked_mut(self.len) = word;
+        self.len = self.len.unchecked_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_capacity_like_defaults() {
+        let vec = vec![Word(1)].with_capacity_like(5).unwrap();
+        assert!(vec.is_empty() && vec.capacity() >= 5);
+        let inline = InlineWordArray::<4>::EMPTY.with_capacity_like(4).unwrap();
+        assert!(inline.is_empty());
+        assert_eq!(inline.with_capacity_like(5).unwrap_err(), CapacityExceeded { required: 5 });
+    }
+
+    #[test]
+    fn inline_capacity_exceeded() {
+        let mut array = InlineWordArray::<2>::with_capacity(2).unwrap();
+        assert_eq!(InlineWordArray::<2>::with_capacity(3).unwrap_err(), CapacityExceeded { required: 3 });
+        array.push(Word(1)).unwrap();
+        array.push(Word(2)).unwrap();
+        assert_eq!(array.push(Word(3)), Err(CapacityExceeded { required: 3 }));
+        assert_eq!(array.as_ref(), &[Word(1), Word(2)]);
+        assert_eq!(array.reserve(usize::MAX), Err(CapacityExceeded::capacity_arithmetic_overflow()));
+        assert!(array.reserve(usize::MAX).unwrap_err().is_capacity_overflow());
+        array.truncate(1);
+        array.reserve(1).unwrap();
+        // Arithmetic past the capacity reports the error instead of writing out of bounds
+        use crate::uint::UnsignedInteger;
+        let max = UnsignedInteger::<InlineWordArray<2>>::try_from_u128(u128::MAX).unwrap();
+        let mut value = max.clone();
+        assert_eq!(value.add_u64(1), Err(CapacityExceeded { required: 3 }));
+        assert_eq!(value.mul_u64(2), Err(CapacityExceeded { required: 3 }));
+        assert_eq!(value, max);
+    }
+
+    #[cfg(feature = "bump")]
+    #[test]
+    fn bump_arena_shared_by_operations() {
+        use crate::uint::UnsignedInteger;
+        let arena = bumpalo::Bump::new();
+        let mut a = UnsignedInteger::from_word_array(BumpWordArray::new_in(&arena));
+        a.set_from_str_radix("340282366920938463463374607431768211457", 10).unwrap();
+        let mut b = UnsignedInteger::from_word_array(BumpWordArray::new_in(&arena));
+        b.set_from_str_radix("18446744073709551629", 10).unwrap();
+        let product = a.mul(&b).unwrap();
+        let (quotient, remainder) = product.div_rem(&b).unwrap();
+        assert_eq!(quotient, a);
+        assert!(remainder.is_empty());
+        let difference = a.checked_sub(&b).unwrap().unwrap();
+        assert_eq!(difference.checked_add(&b).unwrap(), a);
+        let cube = b.pow(3).unwrap();
+        assert_eq!(cube.div_rem(&b).unwrap().0, b.mul(&b).unwrap());
+        assert!(product.gcd(&a).unwrap() == a);
+        let mut copy = a.clone_with_capacity(4).unwrap();
+        copy.mul_u64(3).unwrap();
+        assert_eq!(copy.div_rem(&a).unwrap().0.words(), [Word(3)]);
+        assert!(a.pow_mod(&b, &product).is_ok());
+        // Without the arena, each of these would have failed with `NoArena`
+        assert!(arena.allocated_bytes() > 0);
+        assert_eq!(a.heap_bytes(), 0);
+    }
+
+    #[cfg(feature = "bump")]
+    #[test]
+    fn bump_without_arena() {
+        let array = BumpWordArray::with_capacity(1);
+        assert_eq!(array.unwrap_err(), BumpAllocError::NoArena);
+        let arena = bumpalo::Bump::new();
+        let array = BumpWordArray::new_in(&arena).with_capacity_like(3).unwrap();
+        assert!(array.capacity() >= 3);
+    }
+}
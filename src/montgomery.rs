@@ -0,0 +1,279 @@
+//! Modular exponentiation using Montgomery reduction
+//!
+//! For an odd modulus `n` with `k` words, Montgomery form
+//! represents `x` as `x * R mod n` where `R = 2**(64 * k)`.
+//! Multiplying in this form replaces the division by `n`
+//! with a division by `R`, which is just dropping words.
+use core::cmp::Ordering;
+use thiserror::Error;
+
+use crate::memory::{WordArray, Word, IAllocError};
+use crate::uint::{UnsignedInteger, DivError, sub_words_assign};
+
+/// An error that occurs setting up Montgomery reduction
+#[derive(Error, Debug)]
+pub enum MontgomeryError<A: IAllocError> {
+    /// The modulus was even (or zero)
+    ///
+    /// Montgomery reduction only works for odd moduli,
+    /// so use [UnsignedInteger::pow_mod] instead.
+    #[error("Even modulus, use the generic pow_mod instead")]
+    EvenModulus,
+    /// Allocating space failed
+    #[error("Allocation failed: {cause}")]
+    AllocFailed {
+        /// The underlying allocation error
+        #[from]
+        cause: A
+    }
+}
+
+/// Precomputed values for Montgomery reduction modulo an odd integer
+///
+/// Building this takes time quadratic in the length of the modulus,
+/// so it's most worthwhile when exponentiating repeatedly
+/// with the same modulus (like RSA or Diffie-Hellman).
+pub struct MontgomeryCtx<A: WordArray> {
+    /// The odd modulus `n`
+    modulus: UnsignedInteger<A>,
+    /// `-n**-1 mod 2**64`
+    n_prime: u64,
+    /// `R**2 mod n`, padded with zero words to the length of the modulus
+    r_squared: A
+}
+impl<A: WordArray> MontgomeryCtx<A> {
+    /// Precompute the values needed for reduction modulo the specified integer
+    ///
+    /// Errors with [MontgomeryError::EvenModulus] if the modulus is even,
+    /// or if allocating space fails
+    pub fn new(modulus: &UnsignedInteger<A>) -> Result<Self, MontgomeryError<A::AllocErr>> {
+        if !modulus.is_odd() {
+            return Err(MontgomeryError::EvenModulus);
+        }
+        let len = modulus.len();
+        /*
+         * Newton's method doubles the number of correct low bits each step.
+         * Every odd number is its own inverse modulo 8,
+         * so starting from three bits, five steps gives all 64.
+         */
+        let n0 = modulus.words()[0].0;
+        let mut inverse = n0;
+        for _ in 0..5 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(n0.wrapping_mul(inverse)));
+        }
+        debug_assert_eq!(n0.wrapping_mul(inverse), 1);
+        /*
+         * Compute `R**2 = 2**(128 * len)` by doubling one modulo `n`,
+         * so nothing ever needs more words than the modulus.
+         * That keeps fixed-capacity arrays working, and avoids dividing.
+         */
+        let doublings = len.checked_mul(128)
+            .ok_or_else(A::AllocErr::capacity_arithmetic_overflow)?;
+        let mut r_squared = padded_words(modulus.as_word_array(), &[Word(1)], len)?;
+        for _ in 0..doublings {
+            let words = r_squared.as_mut();
+            let mut carry = 0;
+            for word in words.iter_mut() {
+                let next_carry = word.0 >> 63;
+                word.0 = (word.0 << 1) | carry;
+                carry = next_carry;
+            }
+            // The doubled value is less than twice the modulus
+            if carry != 0 || words.iter().rev().cmp(modulus.words().iter().rev()) != Ordering::Less {
+                sub_words_assign(words, modulus.words());
+            }
+        }
+        Ok(MontgomeryCtx {
+            modulus: modulus.clone_with_capacity(0)?,
+            n_prime: inverse.wrapping_neg(),
+            r_squared
+        })
+    }
+    /// The modulus this context reduces by
+    #[inline]
+    pub fn modulus(&self) -> &UnsignedInteger<A> {
+        &self.modulus
+    }
+    /// Multiply two values in Montgomery form, writing the product into `out`
+    ///
+    /// The left value must be padded to the length of the modulus,
+    /// while the right value may be shorter.
+    fn mul(&self, out: &mut A, left: &[Word], right: &[Word]) {
+        mont_mul_words(out.as_mut(), left, right, self.modulus.words(), self.n_prime)
+    }
+}
+
+impl<A: WordArray> UnsignedInteger<A> {
+    /// Raise this integer to the specified power,
+    /// modulo the odd modulus of the specified context
+    ///
+    /// This gives the same result as [UnsignedInteger::pow_mod],
+    /// but each multiplication is reduced without dividing.
+    ///
+    /// Errors if allocating space fails
+    pub fn pow_mod_mont(&self, exponent: &Self, ctx: &MontgomeryCtx<A>) -> Result<Self, A::AllocErr> {
+        let modulus = &ctx.modulus;
+        let len = modulus.len();
+        let like = modulus.as_word_array();
+        let mut base = if self.cmp_magnitude(modulus) == Ordering::Less {
+            padded_words(like, self.words(), len)?
+        } else {
+            let (_, reduced) = self.div_rem(modulus).map_err(|cause| match cause {
+                DivError::AllocFailed { cause } => cause,
+                DivError::DivisionByZero => unreachable!("Odd modulus")
+            })?;
+            padded_words(like, reduced.words(), len)?
+        };
+        let mut scratch = padded_words(like, &[], len)?;
+        // Convert the base into Montgomery form, as `base * R**2 / R`
+        ctx.mul(&mut scratch, base.as_ref(), ctx.r_squared.as_ref());
+        core::mem::swap(&mut base, &mut scratch);
+        // Start from one in Montgomery form, as `R**2 / R`
+        let mut res = padded_words(like, &[], len)?;
+        ctx.mul(&mut res, ctx.r_squared.as_ref(), &[Word(1)]);
+        /*
+         * Left-to-right binary exponentiation (square-and-multiply),
+         * reading the exponent a bit at a time.
+         */
+        for index in (0..exponent.bit_len()).rev() {
+            ctx.mul(&mut scratch, res.as_ref(), res.as_ref());
+            core::mem::swap(&mut res, &mut scratch);
+            if exponent.get_bit(index) {
+                ctx.mul(&mut scratch, res.as_ref(), base.as_ref());
+                core::mem::swap(&mut res, &mut scratch);
+            }
+        }
+        // Convert back out of Montgomery form, as `res / R`
+        ctx.mul(&mut scratch, res.as_ref(), &[Word(1)]);
+        let mut res = UnsignedInteger::from_word_array(scratch);
+        res.trim();
+        Ok(res)
+    }
+}
+
+/// Copy the specified words into a new array allocated like `like`,
+/// padded with zero words up to the specified length
+fn padded_words<A: WordArray>(like: &A, words: &[Word], len: usize) -> Result<A, A::AllocErr> {
+    debug_assert!(words.len() <= len);
+    let mut res = like.with_capacity_like(len)?;
+    for index in 0..len {
+        let word = words.get(index).copied().unwrap_or(Word(0));
+        unsafe { res.unchecked_push(word) };
+    }
+    Ok(res)
+}
+
+/// Compute `left * right / R mod modulus`, writing the result into `out`
+///
+/// This interleaves multiplication with reduction a word at a time,
+/// known as Coarsely Integrated Operand Scanning (CIOS).
+/// The two words above the top of `out` are kept in locals,
+/// so `out` only needs the same length as the modulus.
+///
+/// Both operands must be less than the modulus.
+/// The left operand must have the same length as the modulus,
+/// while the right operand may be shorter.
+fn mont_mul_words(out: &mut [Word], left: &[Word], right: &[Word], modulus: &[Word], n_prime: u64) {
+    let len = modulus.len();
+    debug_assert!(out.len() == len && left.len() == len && right.len() <= len);
+    for word in out.iter_mut() {
+        *word = Word(0);
+    }
+    let mut top = 0u64;
+    for index in 0..len {
+        // Add `left * right[index]`
+        let scalar = right.get(index).map_or(0, |word| word.0) as u128;
+        let mut carry = 0u64;
+        for (target, word) in out.iter_mut().zip(left) {
+            let sum = target.0 as u128 + word.0 as u128 * scalar + carry as u128;
+            target.0 = sum as u64;
+            carry = (sum >> 64) as u64;
+        }
+        let sum = top as u128 + carry as u128;
+        top = sum as u64;
+        let overflow = (sum >> 64) as u64;
+        /*
+         * Add a multiple of the modulus chosen to zero the low word,
+         * then shift everything down by a word (dividing by `2**64`).
+         */
+        let factor = out[0].0.wrapping_mul(n_prime) as u128;
+        let sum = out[0].0 as u128 + modulus[0].0 as u128 * factor;
+        let mut carry = (sum >> 64) as u64;
+        for word_index in 1..len {
+            let sum = out[word_index].0 as u128 + modulus[word_index].0 as u128 * factor + carry as u128;
+            out[word_index - 1].0 = sum as u64;
+            carry = (sum >> 64) as u64;
+        }
+        let sum = top as u128 + carry as u128;
+        out[len - 1].0 = sum as u64;
+        top = overflow + (sum >> 64) as u64;
+    }
+    // The result is less than twice the modulus, so one subtraction is enough
+    if top != 0 || out.iter().rev().cmp(modulus.iter().rev()) != Ordering::Less {
+        sub_words_assign(out, modulus);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use crate::fixed::U256;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random(rng: &mut SmallRng, len: usize) -> UnsignedInteger {
+        UnsignedInteger::from_words_iter((0..len).map(|_| Word(rng.gen()))).unwrap()
+    }
+    /// Square-and-multiply reducing each product with a full division,
+    /// since [UnsignedInteger::pow_mod] itself uses Montgomery reduction for odd moduli
+    fn pow_mod_by_division(base: &UnsignedInteger, exponent: &UnsignedInteger, modulus: &UnsignedInteger) -> UnsignedInteger {
+        let (_, base) = base.div_rem(modulus).unwrap();
+        let mut res = UnsignedInteger::one().unwrap().div_rem(modulus).unwrap().1;
+        for index in (0..exponent.bit_len()).rev() {
+            res = res.mul_mod(&res, modulus).unwrap();
+            if exponent.get_bit(index) {
+                res = res.mul_mod(&base, modulus).unwrap();
+            }
+        }
+        res
+    }
+
+    #[test]
+    fn matches_division() {
+        let mut rng = SmallRng::seed_from_u64(311);
+        for len in 1..=6 {
+            for _ in 0..30 {
+                let mut modulus = random(&mut rng, len);
+                modulus.set_bit(0, true).unwrap();
+                let ctx = MontgomeryCtx::new(&modulus).unwrap();
+                // Include bases at least as large as the modulus
+                let (base_len, exponent_len) = (rng.gen_range(0..=len + 1), rng.gen_range(0..=2));
+                let (base, exponent) = (random(&mut rng, base_len), random(&mut rng, exponent_len));
+                let res = base.pow_mod_mont(&exponent, &ctx).unwrap();
+                assert_eq!(res, pow_mod_by_division(&base, &exponent, &modulus), "{:?}**{:?} % {:?}", base, exponent, modulus);
+                assert_eq!(res, base.pow_mod(&exponent, &modulus).unwrap());
+                assert_eq!(res.check_invariants(), Ok(()));
+            }
+        }
+    }
+    #[test]
+    fn edge_moduli() {
+        let small = |value: u64| UnsignedInteger::<Vec<Word>>::from(value);
+        // Everything is zero modulo one, even a zero power
+        let ctx = MontgomeryCtx::new(&small(1)).unwrap();
+        assert!(small(5).pow_mod_mont(&small(0), &ctx).unwrap().is_empty());
+        // The largest odd modulus of each length, where the top word is all ones
+        let modulus = UnsignedInteger::from(u128::MAX);
+        let ctx = MontgomeryCtx::new(&modulus).unwrap();
+        let base = UnsignedInteger::from(u128::MAX - 1);
+        assert_eq!(base.pow_mod_mont(&small(3), &ctx).unwrap(), pow_mod_by_division(&base, &small(3), &modulus));
+        assert!(matches!(MontgomeryCtx::new(&small(10)), Err(MontgomeryError::EvenModulus)));
+        assert!(matches!(MontgomeryCtx::new(&UnsignedInteger::<Vec<Word>>::ZERO), Err(MontgomeryError::EvenModulus)));
+        // A full fixed-width modulus never needs more words
+        let ctx = MontgomeryCtx::new(&U256::MAX).unwrap();
+        let wide = UnsignedInteger::from_word_array(U256::MAX.words().to_vec());
+        let expected = pow_mod_by_division(&small(7), &small(1000), &wide);
+        assert_eq!(U256::from_u64_const(7).pow_mod_mont(&U256::from_u64_const(1000), &ctx).unwrap().words(), expected.words());
+    }
+}
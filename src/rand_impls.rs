@@ -0,0 +1,154 @@
+//! Random integers using rand
+use rand::RngCore;
+
+use crate::memory::{WordArray, Word, IAllocError};
+use crate::uint::UnsignedInteger;
+use crate::arith_utils::ArithUtil;
+use core::convert::TryFrom;
+
+impl<A: WordArray> UnsignedInteger<A> {
+    /// Generate a uniformly random integer with at most the specified number of bits,
+    /// in the range `[0, 2**bits)`
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn random_bits<R: RngCore + ?Sized>(rng: &mut R, bits: u64) -> Result<Self, A::AllocErr> {
+        Self::ZERO.random_bits_like(rng, bits)
+    }
+    /// Generate a uniformly random integer like [UnsignedInteger::random_bits],
+    /// allocating like this integer
+    fn random_bits_like<R: RngCore + ?Sized>(&self, rng: &mut R, bits: u64) -> Result<Self, A::AllocErr> {
+        let len = usize::try_from(bits.divide_round_up(Word::BITS))
+            .map_err(|_| A::AllocErr::capacity_arithmetic_overflow())?;
+        let mut words = self.words.with_capacity_like(len)?;
+        for _ in 0..len {
+            unsafe { words.unchecked_push(Word(rng.next_u64())) };
+        }
+        let top_bits = bits % Word::BITS;
+        if top_bits != 0 {
+            words.as_mut()[len - 1].0 &= (1 << top_bits) - 1;
+        }
+        // The top words may have come out zero
+        let mut res = UnsignedInteger::from_word_array(words);
+        res.trim();
+        Ok(res)
+    }
+    /// Generate a uniformly random integer in the range `[0, bound)`
+    ///
+    /// This uses rejection sampling, drawing random integers
+    /// with the same bit length as the bound until one is in range.
+    /// Each attempt succeeds with probability above one half.
+    ///
+    /// Errors if allocating space fails
+    ///
+    /// ## Panics
+    /// If the bound is zero
+    pub fn random_below<R: RngCore + ?Sized>(rng: &mut R, bound: &Self) -> Result<Self, A::AllocErr> {
+        assert!(!bound.is_empty(), "Zero bound");
+        let bits = bound.bit_len();
+        loop {
+            let candidate = bound.random_bits_like(rng, bits)?;
+            if candidate < *bound {
+                return Ok(candidate);
+            }
+        }
+    }
+    /// Check if this integer is probably prime,
+    /// using the specified number of rounds of the Miller-Rabin test
+    ///
+    /// Primes always pass. Each round uses a random witness,
+    /// and catches a composite with probability at least 3/4,
+    /// so a composite passes all the rounds with probability at most `4**-rounds`.
+    ///
+    /// Errors if allocating space fails
+    pub fn is_probable_prime<R: RngCore + ?Sized>(&self, rng: &mut R, rounds: usize) -> Result<bool, A::AllocErr> {
+        match self.words() {
+            [] => return Ok(false),
+            [Word(n)] if *n <= 3 => return Ok(*n >= 2),
+            _ if self.is_even() => return Ok(false),
+            _ => {}
+        }
+        let (n_minus_one, d, s) = self.miller_rabin_decompose()?;
+        // Witnesses are in `[2, n - 2]`, so are drawn below `n - 3` and offset by two
+        let mut two = self.with_capacity_like(1)?;
+        two.set(2)?;
+        let mut witness_bound = n_minus_one.clone_with_capacity(0)?;
+        witness_bound.unchecked_sub(&two);
+        for _ in 0..rounds {
+            let mut witness = Self::random_below(rng, &witness_bound)?;
+            witness.add_u64(2)?;
+            if !self.miller_rabin_round(&witness, &n_minus_one, &d, s)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{CapacityExceeded, InlineWordArray};
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+    use alloc::vec::Vec;
+    use alloc::vec;
+    #[test]
+    fn random_bits() {
+        let mut rng = SmallRng::seed_from_u64(308);
+        for &bits in &[0, 1, 8, 63, 64, 65, 200] {
+            let mut top_bit_seen = false;
+            for _ in 0..200 {
+                let value: UnsignedInteger = UnsignedInteger::random_bits(&mut rng, bits).unwrap();
+                assert!(value.bit_len() <= bits);
+                assert_eq!(value.check_invariants(), Ok(()));
+                top_bit_seen |= bits > 0 && value.bit_len() == bits;
+            }
+            assert_eq!(top_bit_seen, bits > 0, "{} bits", bits);
+        }
+        let inline = UnsignedInteger::<InlineWordArray<1>>::random_bits(&mut rng, 65);
+        assert_eq!(inline.unwrap_err(), CapacityExceeded { required: 2 });
+    }
+    #[test]
+    fn random_below() {
+        let mut rng = SmallRng::seed_from_u64(308);
+        // Just above a power of two rejects almost half the candidates
+        let bounds: Vec<UnsignedInteger> = vec![
+            1u64.into(), 2u64.into(), 3u64.into(), 1000u64.into(), ((1u128 << 64) + 1).into(), u128::MAX.into()
+        ];
+        for bound in &bounds {
+            let mut seen = Vec::new();
+            for _ in 0..2000 {
+                let value = UnsignedInteger::random_below(&mut rng, bound).unwrap();
+                assert!(value < *bound, "{:?} >= {:?}", value, bound);
+                if seen.len() < 3 && !seen.contains(&value) {
+                    seen.push(value);
+                }
+            }
+            // Every small range is fully covered
+            assert_eq!(seen.len(), u64::try_from(bound).map_or(3, |bound| bound.min(3) as usize));
+        }
+    }
+    #[test]
+    fn probable_primes() {
+        let mut rng = SmallRng::seed_from_u64(309);
+        let is_prime = |value: &UnsignedInteger, rng: &mut SmallRng| value.is_probable_prime(rng, 20).unwrap();
+        // Agrees with trial division for small values
+        for n in 0..500u64 {
+            let expected = n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0);
+            assert_eq!(is_prime(&n.into(), &mut rng), expected, "{}", n);
+        }
+        // Mersenne primes
+        assert!(is_prime(&((1u64 << 61) - 1).into(), &mut rng));
+        assert!(is_prime(&(u128::MAX >> 1).into(), &mut rng));
+        // A Carmichael number fools Fermat's test, but not this one
+        assert!(!is_prime(&561u64.into(), &mut rng));
+        assert!(!is_prime(&(1_000_003u64 * 1_000_033).into(), &mut rng));
+        let semiprime = UnsignedInteger::mul(&((1u64 << 61) - 1).into(), &(u128::MAX >> 39).into()).unwrap();
+        assert!(!is_prime(&semiprime, &mut rng));
+        // The Fermat number `2**128 + 1` is composite
+        let mut fermat = UnsignedInteger::from(1u64) << 128;
+        fermat.add_u64(1).unwrap();
+        assert!(!is_prime(&fermat, &mut rng));
+    }
+}
@@ -0,0 +1,110 @@
+//! Implementations of serde's traits
+use core::fmt::{self, Formatter};
+use core::marker::PhantomData;
+use alloc::vec::Vec;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::memory::WordArray;
+use crate::uint::UnsignedInteger;
+
+impl<A: WordArray> Serialize for UnsignedInteger<A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_str_radix(10))
+        } else {
+            serializer.serialize_bytes(&self.to_le_bytes())
+        }
+    }
+}
+
+impl<'de, A: WordArray> Deserialize<'de> for UnsignedInteger<A> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(UnsignedIntegerVisitor(PhantomData))
+        } else {
+            deserializer.deserialize_bytes(UnsignedIntegerVisitor(PhantomData))
+        }
+    }
+}
+
+/// Accepts either a decimal string or little-endian bytes,
+/// regardless of what the format prefers
+struct UnsignedIntegerVisitor<A: WordArray>(PhantomData<A>);
+impl<'de, A: WordArray> Visitor<'de> for UnsignedIntegerVisitor<A> {
+    type Value = UnsignedInteger<A>;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("an unsigned integer as a decimal string or little-endian bytes")
+    }
+
+    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Self::Value, E> {
+        let mut res = UnsignedInteger::ZERO;
+        res.set(val).map_err(E::custom)?;
+        Ok(res)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        crate::string::parse_unsigned_radix(s, 10).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        UnsignedInteger::from_le_bytes(bytes).map_err(E::custom)
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Self::Value, S::Error> {
+        // Some formats represent bytes as a sequence
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::memory::InlineWordArray;
+
+    fn samples() -> Vec<UnsignedInteger> {
+        let mut samples = vec![UnsignedInteger::ZERO, UnsignedInteger::from(1u64), UnsignedInteger::from(u64::MAX)];
+        samples.push("123456789012345678901234567890123456789012345678901234567890".parse().unwrap());
+        samples
+    }
+
+    #[test]
+    fn json_round_trip() {
+        for value in samples() {
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(json, format!("\"{}\"", value.to_str_radix(10)));
+            assert_eq!(serde_json::from_str::<UnsignedInteger>(&json).unwrap(), value);
+        }
+    }
+    #[test]
+    fn bincode_round_trip() {
+        for value in samples() {
+            let encoded = bincode::serialize(&value).unwrap();
+            // A length prefix, followed by the little-endian bytes
+            let bytes = value.to_le_bytes();
+            assert_eq!(encoded[..8], (bytes.len() as u64).to_le_bytes());
+            assert_eq!(encoded[8..], bytes[..]);
+            assert_eq!(bincode::deserialize::<UnsignedInteger>(&encoded).unwrap(), value);
+        }
+    }
+    #[test]
+    fn rejects_invalid_input() {
+        let negative = serde_json::from_str::<UnsignedInteger>("\"-5\"").unwrap_err();
+        assert!(negative.to_string().contains("Signs are forbidden"), "{}", negative);
+        assert!(serde_json::from_str::<UnsignedInteger>("\"12a\"").is_err());
+        assert!(serde_json::from_str::<UnsignedInteger>("\"\"").is_err());
+        assert!(serde_json::from_str::<UnsignedInteger>("[1, 2]").is_err());
+        // Too many bytes for a single inline word
+        let encoded = bincode::serialize(&UnsignedInteger::<Vec<_>>::from(u128::MAX)).unwrap();
+        assert!(bincode::deserialize::<UnsignedInteger<InlineWordArray<1>>>(&encoded).is_err());
+        assert!(bincode::deserialize::<UnsignedInteger<InlineWordArray<2>>>(&encoded).is_ok());
+        // A length prefix promising more bytes than there are
+        assert!(bincode::deserialize::<UnsignedInteger>(&[9, 0, 0, 0, 0, 0, 0, 0, 1]).is_err());
+    }
+}
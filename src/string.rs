@@ -1,7 +1,14 @@
 //! Conversions to/from strings
+use core::convert::TryFrom;
+use core::str::FromStr;
+#[cfg(feature = "alloc")]
+use core::fmt::{self, Binary, Display, Formatter, LowerHex, Octal, UpperHex, Write};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec, vec::Vec};
+
 use thiserror::Error;
 use crate::memory::{IAllocError, WordArray, Word};
-use crate::uint::UnsignedInteger;
+use crate::uint::{UnsignedInteger, mul_add_small_assign};
 use crate::arith_utils::ArithUtil;
 
 /// An error that occurs parsing a string
@@ -12,6 +19,11 @@ pub enum ParseIntError<A: IAllocError> {
         digit: char,
         radix: u32
     },
+    #[error("Invalid digit value {value} in base {radix}")]
+    InvalidDigitValue {
+        value: u8,
+        radix: u32
+    },
     #[error("Signs are forbidden in unsigned integers")]
     ForbiddenNegative,
     #[error("Empty string")]
@@ -23,49 +35,879 @@ pub enum ParseIntError<A: IAllocError> {
     }
 }
 
-pub(crate) fn parse_unsigned_radix<A: WordArray>(mut s: &str, radix: u32) -> Result<UnsignedInteger<A>, ParseIntError<A::AllocErr>> {
+pub(crate) fn parse_unsigned_radix<A: WordArray>(s: &str, radix: u32) -> Result<UnsignedInteger<A>, ParseIntError<A::AllocErr>> {
+    let mut res = UnsignedInteger::ZERO;
+    parse_unsigned_radix_impl(&mut res, s, radix, false)?;
+    Ok(res)
+}
+
+/// Parse an unsigned integer like [parse_unsigned_radix],
+/// but reusing the array of an existing integer
+///
+/// On error, the integer is left as zero.
+pub(crate) fn parse_unsigned_radix_into<A: WordArray>(res: &mut UnsignedInteger<A>, s: &str, radix: u32) -> Result<(), ParseIntError<A::AllocErr>> {
+    parse_unsigned_radix_impl(res, s, radix, false).inspect_err(|_| res.words.clear())
+}
+
+/// Parse an unsigned integer like [parse_unsigned_radix],
+/// but allowing underscores to separate digits (like `1_000_000`)
+///
+/// Leading, trailing, or consecutive underscores are rejected.
+pub(crate) fn parse_unsigned_radix_with_separators<A: WordArray>(s: &str, radix: u32) -> Result<UnsignedInteger<A>, ParseIntError<A::AllocErr>> {
+    let mut res = UnsignedInteger::ZERO;
+    parse_unsigned_radix_impl(&mut res, s, radix, true)?;
+    Ok(res)
+}
+
+/// Parse an unsigned integer from a byte slice of ASCII digits
+///
+/// This skips validating the bytes as UTF-8.
+/// Any non-ASCII byte is an invalid digit,
+/// reported as the replacement character `U+FFFD`.
+pub(crate) fn parse_unsigned_radix_bytes<A: WordArray>(bytes: &[u8], radix: u32) -> Result<UnsignedInteger<A>, ParseIntError<A::AllocErr>> {
+    let mut res = UnsignedInteger::ZERO;
+    parse_ascii_digits(&mut res, bytes, radix, false)?;
+    Ok(res)
+}
+
+fn parse_unsigned_radix_impl<A: WordArray>(res: &mut UnsignedInteger<A>, s: &str, radix: u32, separators: bool) -> Result<(), ParseIntError<A::AllocErr>> {
+    parse_ascii_digits(res, s.as_bytes(), radix, separators).map_err(|err| report_non_ascii(s, err))
+}
+
+/// Report the actual character when a non-ASCII byte of `s` was an invalid digit,
+/// instead of the replacement character
+fn report_non_ascii<E: IAllocError>(s: &str, err: ParseIntError<E>) -> ParseIntError<E> {
+    match err {
+        ParseIntError::InvalidDigit { digit: char::REPLACEMENT_CHARACTER, radix } => {
+            /*
+             * Every byte before the failing one was a valid ASCII digit,
+             * so the first non-ASCII character is the one that failed.
+             */
+            let digit = s.chars().find(|c| !c.is_ascii()).unwrap_or(char::REPLACEMENT_CHARACTER);
+            ParseIntError::InvalidDigit { digit, radix }
+        },
+        err => err
+    }
+}
+
+/// Check that the bytes are all valid digits, with an optional leading `+`
+///
+/// Returns the digits without the sign,
+/// along with the number of digits after any leading zeros.
+/// Every digit is checked up front, so invalid input is rejected before allocating.
+fn check_ascii_digits<E: IAllocError>(mut bytes: &[u8], radix: u32, separators: bool) -> Result<(&[u8], usize), ParseIntError<E>> {
     assert!((2..=36).contains(&radix), "Invalid radix: {}", radix);
-    if s.starts_with("-") {
+    match bytes.first() {
+        Some(b'-') => return Err(ParseIntError::ForbiddenNegative),
+        Some(b'+') => bytes = &bytes[1..],
+        _ => {}
+    }
+    if bytes.is_empty() {
+        return Err(ParseIntError::EmptyString)
+    }
+    if separators && (bytes.starts_with(b"_") || bytes.ends_with(b"_") || bytes.windows(2).any(|pair| pair == b"__")) {
+        return Err(ParseIntError::InvalidDigit { digit: '_', radix })
+    }
+    let mut significant_digits = 0usize;
+    for &digit in bytes {
+        if separators && digit == b'_' {
+            continue;
+        }
+        let digit_val = digit_value(digit);
+        if digit_val as u32 >= radix {
+            let digit = if digit.is_ascii() { digit as char } else { char::REPLACEMENT_CHARACTER };
+            return Err(ParseIntError::InvalidDigit {
+                digit, radix
+            })
+        }
+        // Leading zeros don't take up any space
+        if significant_digits > 0 || digit_val != 0 {
+            significant_digits += 1;
+        }
+    }
+    Ok((bytes, significant_digits))
+}
+
+/// Parse a decimal integer into an array of exactly the final length
+///
+/// See [UnsignedInteger::from_decimal_exact].
+pub(crate) fn parse_decimal_exact<A: WordArray>(s: &str) -> Result<UnsignedInteger<A>, ParseIntError<A::AllocErr>> {
+    let (digits, significant_digits) = check_ascii_digits(s.as_bytes(), 10, false)
+        .map_err(|err| report_non_ascii(s, err))?;
+    let mut len = min_decimal_words::<A::AllocErr>(significant_digits)?;
+    let mut res = UnsignedInteger::from_word_array(A::with_capacity(len)?);
+    for &digit in digits {
+        let digit = digit_value(digit) as u64;
+        if res.words.len() < len {
+            unsafe { res.unchecked_mul_add_u64(10, digit) };
+            continue;
+        }
+        let carry = mul_add_small_assign(res.words.as_mut(), 10, digit);
+        if carry != 0 {
+            /*
+             * The value outgrew the lower bound, so move it to a new array
+             * with room for exactly one more word. The old words are still exact,
+             * since they never overflowed before this step.
+             */
+            len += 1;
+            let mut grown = res.words.with_capacity_like(len)?;
+            for &word in res.words() {
+                unsafe { grown.unchecked_push(word) };
+            }
+            unsafe { grown.unchecked_push(Word(carry)) };
+            res = UnsignedInteger::from_word_array(grown);
+        }
+    }
+    Ok(res)
+}
+
+/// A lower bound on the number of words needed to hold
+/// `len` decimal digits without any leading zeros
+///
+/// This is tighter than [min_words], and almost always exact.
+fn min_decimal_words<E: IAllocError>(len: usize) -> Result<usize, E> {
+    if len == 0 {
+        return Ok(0);
+    }
+    /*
+     * The value is at least `10**(len - 1)`, which needs more than
+     * `(len - 1) * log2(10)` bits. The fractional part of the logarithm is
+     * a 64-bit fixed point number, rounded down to keep this a lower bound.
+     */
+    const LOG2_10_FRACTION: u128 = 0x5269_E12F_346E_2BF9;
+    let digits = (len - 1) as u128;
+    let min_bits = digits * 3 + ((digits * LOG2_10_FRACTION) >> 64) + 1;
+    let min_words = min_bits.div_ceil(Word::BITS as u128);
+    usize::try_from(min_words)
+        .map_err(|_| E::capacity_arithmetic_overflow())
+}
+
+/// Parse ASCII digits into the specified integer, reusing its array
+///
+/// On error, the integer may be left holding a partial result.
+fn parse_ascii_digits<A: WordArray>(res: &mut UnsignedInteger<A>, bytes: &[u8], radix: u32, separators: bool) -> Result<(), ParseIntError<A::AllocErr>> {
+    let (bytes, significant_digits) = check_ascii_digits(bytes, radix, separators)?;
+    res.words.clear();
+    res.words.reserve(min_words::<A::AllocErr>(radix, significant_digits)?)?;
+    for &digit in bytes {
+        if separators && digit == b'_' {
+            continue;
+        }
+        // Horner's method, growing past `min_words` only if needed
+        res.mul_add_u64(radix as u64, digit_value(digit) as u64)?;
+    }
+    Ok(())
+}
+
+/// The value of an ASCII digit in bases up to 36,
+/// or [u8::MAX] if it isn't a digit in any of them
+#[inline]
+fn digit_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'A'..=b'Z' => digit - b'A' + 10,
+        b'a'..=b'z' => digit - b'a' + 10,
+        _ => u8::MAX
+    }
+}
+
+/// Parse a hexadecimal string,
+/// packing each group of 16 digits directly into a word
+///
+/// This avoids the multiplication per digit of Horner's method.
+pub(crate) fn parse_unsigned_hex<A: WordArray>(mut s: &str) -> Result<UnsignedInteger<A>, ParseIntError<A::AllocErr>> {
+    if s.starts_with('-') {
         return Err(ParseIntError::ForbiddenNegative);
-    } else if s.starts_with("+") {
+    } else if s.starts_with('+') {
         s = &s[1..];
     }
     if s.is_empty() {
         return Err(ParseIntError::EmptyString)
     }
+    if let Some(digit) = s.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(ParseIntError::InvalidDigit { digit, radix: 16 })
+    }
+    // Leading zeros would otherwise count against a fixed capacity
+    let s = s.trim_start_matches('0');
+    // Each word holds exactly 16 hex digits
+    let mut words = A::with_capacity(s.len().divide_round_up(16))?;
+    for chunk in s.as_bytes().rchunks(16) {
+        let mut word = 0u64;
+        for &digit in chunk {
+            // We already checked these are all valid hex digits
+            word = (word << 4) | (digit as char).to_digit(16).unwrap() as u64;
+        }
+        unsafe { words.unchecked_push(Word(word)) };
+    }
+    // Without leading zeros, the top word is nonzero
+    let res = UnsignedInteger::from_word_array(words);
+    debug_assert_eq!(res.check_invariants(), Ok(()));
+    Ok(res)
+}
+
+impl<A: WordArray> FromStr for UnsignedInteger<A> {
+    type Err = ParseIntError<A::AllocErr>;
+
+    /// Parse a string, detecting the radix from its prefix
+    ///
+    /// Strings starting with `0x` (or `0X`) are hexadecimal,
+    /// `0o` is octal, `0b` is binary, and anything else is decimal.
+    /// A bare prefix is an empty string.
+    ///
+    /// Like [Num::from_str_radix](num_traits::Num::from_str_radix),
+    /// surrounding ASCII whitespace is ignored.
+    /// An optional `+` sign goes before the prefix, as in `+0x10`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_matches(|c: char| c.is_ascii_whitespace());
+        let s = match s.as_bytes().first() {
+            Some(b'-') => return Err(ParseIntError::ForbiddenNegative),
+            Some(b'+') => &s[1..],
+            _ => s
+        };
+        let (digits, radix) = match s.get(..2) {
+            Some("0x") | Some("0X") => (&s[2..], 16),
+            Some("0o") => (&s[2..], 8),
+            Some("0b") => (&s[2..], 2),
+            _ => (s, 10)
+        };
+        // The sign was already stripped, so another one is just an invalid digit
+        match digits.as_bytes().first() {
+            Some(b'+') => Err(ParseIntError::InvalidDigit { digit: '+', radix }),
+            Some(b'-') => Err(ParseIntError::ForbiddenNegative),
+            _ => parse_unsigned_radix(digits, radix)
+        }
+    }
+}
+
+/// Build an unsigned integer from raw digit values,
+/// most significant first
+pub(crate) fn unsigned_from_digits<A: WordArray>(digits: &[u8], radix: u32) -> Result<UnsignedInteger<A>, ParseIntError<A::AllocErr>> {
+    assert!((2..=256).contains(&radix), "Invalid radix: {}", radix);
+    if digits.is_empty() {
+        return Err(ParseIntError::EmptyString)
+    }
+    if let Some(&value) = digits.iter().find(|&&value| value as u32 >= radix) {
+        return Err(ParseIntError::InvalidDigitValue {
+            value, radix
+        })
+    }
+    let significant_digits = digits.iter().skip_while(|&&value| value == 0).count();
+    let min_capacity = min_words::<A::AllocErr>(radix, significant_digits)?;
+    let mut res = UnsignedInteger::from_word_array(A::with_capacity(min_capacity)?);
+    for &value in digits {
+        // Horner's method, growing past `min_words` only if needed
+        res.mul_add_u64(radix as u64, value as u64)?;
+    }
+    Ok(res)
+}
+
+/// The minimum number of words needed to hold
+/// `len` digits in the specified radix, without any leading zeros
+///
+/// Since this never exceeds the length of the result,
+/// reserving it up front can't make a fixed-capacity array fail
+/// for a value that fits.
+fn min_words<E: IAllocError>(radix: u32, len: usize) -> Result<usize, E> {
+    if len == 0 {
+        return Ok(0);
+    }
+    /*
+     * With a nonzero leading digit, `n` digits are at least `radix**(n - 1)`,
+     * which needs more than `(n - 1) * log2(radix)` bits.
+     * Rounding the logarithm down keeps this a lower bound.
+     * It's at least 60% of the actual length (90% for decimal),
+     * so growing the rest of the way only takes a reallocation or two.
+     */
+    let floor_log_radix = radix.checked_floor_log2().unwrap();
+    (floor_log_radix as u64).checked_mul((len - 1) as u64)
+        .map(|min_bits| (min_bits + 1).divide_round_up(Word::BITS))
+        .and_then(|min_words| usize::try_from(min_words).ok())
+        .ok_or_else(E::capacity_arithmetic_overflow)
+}
+
+/// Format an unsigned integer in the specified radix,
+/// using lowercase letters for digits past nine
+#[cfg(feature = "alloc")]
+pub(crate) fn format_unsigned_radix<A: WordArray>(value: &UnsignedInteger<A>, radix: u32) -> String {
+    format_unsigned_radix_impl(value, radix, false)
+}
+
+/// Format an unsigned integer like [format_unsigned_radix],
+/// formatting the halves of large splits in parallel
+#[cfg(feature = "rayon")]
+pub(crate) fn format_unsigned_radix_parallel<A: WordArray>(value: &UnsignedInteger<A>, radix: u32) -> String {
+    format_unsigned_radix_impl(value, radix, true)
+}
+
+/// The number of words past which radix formatting splits an integer in two,
+/// with a division by a power of the radix, and formats each half separately
+///
+/// Below this, repeatedly dividing by a single word is faster.
+#[cfg(feature = "alloc")]
+const FORMAT_SPLIT_THRESHOLD: usize = 32;
+
+/// The number of words past which the halves of a split are formatted in parallel
+#[cfg(feature = "rayon")]
+const PARALLEL_FORMAT_THRESHOLD: usize = 512;
+
+/// The largest power of a radix that fits in a word,
+/// giving a whole chunk of digits per division
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone)]
+struct DigitChunk {
+    radix: u64,
+    divisor: u64,
+    digits: usize
+}
+#[cfg(feature = "alloc")]
+impl DigitChunk {
+    fn new(radix: u32) -> Self {
+        let radix = radix as u64;
+        let (mut divisor, mut digits) = (radix, 1);
+        while let Some(next) = divisor.checked_mul(radix) {
+            divisor = next;
+            digits += 1;
+        }
+        DigitChunk { radix, divisor, digits }
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn format_unsigned_radix_impl<A: WordArray>(value: &UnsignedInteger<A>, radix: u32, parallel: bool) -> String {
+    assert!((2..=36).contains(&radix), "Invalid radix: {}", radix);
+    if value.words().is_empty() {
+        return "0".into();
+    }
+    let chunk = DigitChunk::new(radix);
+    // Dividing in place would clobber the original, so work on a copy
+    let value = UnsignedInteger::<Vec<Word>>::from_word_array(value.words().to_vec());
+    let digits = if value.len() > FORMAT_SPLIT_THRESHOLD {
+        // Each power is the square of the last, up to half the length of the value
+        let mut powers = vec![UnsignedInteger::<Vec<Word>>::from(chunk.divisor)];
+        loop {
+            let last = powers.last().unwrap();
+            if last.len() * 4 > value.len() {
+                break;
+            }
+            let next = UnsignedInteger::mul(last, last).unwrap();
+            powers.push(next);
+        }
+        format_split(value, &powers, chunk, 0, parallel)
+    } else {
+        format_chunks(value, chunk, 0)
+    };
+    String::from_utf8(digits).unwrap()
+}
+
+/// Format an integer by splitting it in two, padding with leading zeros to `min_digits`
+///
+/// Dividing by `powers[i] = chunk.divisor**(2**i)` splits off the low `chunk.digits * 2**i` digits.
+/// The halves are independent after that, so they can be formatted in parallel.
+#[cfg(feature = "alloc")]
+#[cfg_attr(not(feature = "rayon"), allow(clippy::only_used_in_recursion))]
+fn format_split(value: UnsignedInteger<Vec<Word>>, powers: &[UnsignedInteger<Vec<Word>>], chunk: DigitChunk, min_digits: usize, parallel: bool) -> Vec<u8> {
+    if value.len() <= FORMAT_SPLIT_THRESHOLD {
+        return format_chunks(value, chunk, min_digits);
+    }
+    // Split with the largest power that's at most half the length
+    let index = powers.iter().rposition(|power| power.len() * 2 <= value.len()).unwrap();
+    // Can't fail, since the power is nonzero and vectors never fail to allocate
+    let (high, low) = value.div_rem(&powers[index]).unwrap();
+    let low_digits = chunk.digits << index;
+    let high_digits = min_digits.saturating_sub(low_digits);
+    #[cfg(feature = "rayon")]
+    if parallel && value.len() > PARALLEL_FORMAT_THRESHOLD {
+        let (mut high, low) = rayon::join(
+            || format_split(high, powers, chunk, high_digits, parallel),
+            || format_split(low, powers, chunk, low_digits, parallel)
+        );
+        high.extend_from_slice(&low);
+        return high;
+    }
+    let mut digits = format_split(high, powers, chunk, high_digits, parallel);
+    digits.extend_from_slice(&format_split(low, powers, chunk, low_digits, parallel));
+    digits
+}
+
+/// Format an integer by repeatedly dividing off a chunk of digits,
+/// padding with leading zeros to `min_digits`
+#[cfg(feature = "alloc")]
+fn format_chunks(mut remaining: UnsignedInteger<Vec<Word>>, chunk: DigitChunk, min_digits: usize) -> Vec<u8> {
+    // The digits in reverse order (least significant first)
+    let mut digits = Vec::with_capacity((remaining.len() * 64).max(min_digits));
+    while !remaining.is_empty() {
+        // Can't fail, since the divisor is nonzero and division never allocates
+        let mut remainder = remaining.div_rem_u64(chunk.divisor).unwrap();
+        // The final (most significant) chunk has no leading zeros
+        let is_last = remaining.is_empty();
+        for _ in 0..chunk.digits {
+            if is_last && remainder == 0 {
+                break;
+            }
+            let digit = core::char::from_digit((remainder % chunk.radix) as u32, chunk.radix as u32).unwrap();
+            digits.push(digit as u8);
+            remainder /= chunk.radix;
+        }
+    }
+    if digits.len() < min_digits {
+        digits.resize(min_digits, b'0');
+    }
+    digits.reverse();
+    digits
+}
+
+/// Every power of ten that fits in a word
+#[cfg(feature = "alloc")]
+const POWERS_OF_TEN: [u64; 20] = {
+    let mut res = [1u64; 20];
+    let mut index = 1;
+    while index < res.len() {
+        res[index] = res[index - 1] * 10;
+        index += 1;
+    }
+    res
+};
+
+/// The number of decimal digits needed to format an unsigned integer
+#[cfg(feature = "alloc")]
+pub(crate) fn decimal_len<A: WordArray>(value: &UnsignedInteger<A>) -> u64 {
+    let bits = value.bit_len();
+    if bits == 0 {
+        return 1;
+    }
     /*
-     * Allocation will probably be more expensive
-     * than a little bit of this math.
-     * For 'n' digits in base 10, there are 10**n possibilities,
-     * which take up ceil(log2(10**n)) possibilities.
-     * By log rules, this reduces too ceil(log2(10)*n),
-     * or ceil(log2(10))*n.
+     * Since `2**(bits - 1) <= value < 2**bits`, the number of digits is
+     * between `floor((bits - 1) * log10(2)) + 1` and `floor(bits * log10(2)) + 1`.
+     * These differ by at most one, so starting from the lower estimate,
+     * there is one more digit exactly when `value >= 10**estimate`.
      *
+     * The logarithm is a 64-bit fixed point fraction, to avoid floating point.
      */
-    let ceil_log_radix = radix.ceil_log2();
-    let max_capacity = match (ceil_log_radix as u64).checked_mul(s.len() as u64) {
-        Some(max_bits) => {
-            max_bits.divide_round_up(Word::BITS)
+    const LOG10_2: u128 = 0x4D10_4D42_7DE7_FBCC;
+    let estimate = (((bits - 1) as u128 * LOG10_2) >> 64) as u64 + 1;
+    let more_digits = match POWERS_OF_TEN.get(estimate as usize) {
+        Some(&power) => value.cmp_u64(power) != core::cmp::Ordering::Less,
+        None => {
+            let exponent = u32::try_from(estimate).expect("Decimal length overflowed");
+            let power = UnsignedInteger::<Vec<Word>>::from(10u64).pow(exponent).unwrap();
+            value.cmp_magnitude(&power) != core::cmp::Ordering::Less
+        }
+    };
+    estimate + more_digits as u64
+}
+
+/// Format an unsigned integer approximately in scientific notation,
+/// like `1.234E56`
+///
+/// The mantissa is rounded (half up) to the specified number of significant digits,
+/// padding with zeros if the integer has fewer digits.
+#[cfg(feature = "alloc")]
+pub(crate) fn format_scientific<A: WordArray>(value: &UnsignedInteger<A>, sig_digits: usize) -> String {
+    let sig_digits = sig_digits.max(1);
+    let len = decimal_len(value);
+    let mut exponent = len - 1;
+    // Only the significant digits are formatted, plus one more to round with
+    let mut mantissa = match len.checked_sub(sig_digits as u64 + 1) {
+        Some(dropped) if dropped > 0 => {
+            let dropped = u32::try_from(dropped).expect("Decimal length overflowed");
+            let value = UnsignedInteger::<Vec<Word>>::from_word_array(value.words().to_vec());
+            let power = UnsignedInteger::<Vec<Word>>::from(10u64).pow(dropped).unwrap();
+            let (top, _) = value.div_rem(&power).unwrap();
+            format_unsigned_radix(&top, 10).into_bytes()
         },
-        None => return Err(ParseIntError::AllocFailed {
-            cause: A::AllocErr::capacity_arithmetic_overflow()
-        })
+        _ => format_unsigned_radix(value, 10).into_bytes()
     };
-    let mut res = UnsignedInteger::from_word_array(A::with_capacity(max_capacity)?);
-    res.set(1);
-    for digit in s.chars() {
-        let digit_val = match digit {
-            '0'..='9' => digit as u8 - b'0',
-            'A'..='Z' => digit as u8 - b'A',
-            'a'..='z' => digit as u8 - b'a',
-            _ => u8::MAX
-        };
-        if digit_val as u32 >= radix {
-            return Err(ParseIntError::InvalidDigit {
-                digit, radix
-            })
+    if mantissa.len() > sig_digits {
+        let rounding_digit = mantissa.pop().unwrap();
+        if rounding_digit >= b'5' {
+            match mantissa.iter().rposition(|&digit| digit != b'9') {
+                Some(index) => {
+                    mantissa[index] += 1;
+                    mantissa[index + 1..].fill(b'0');
+                },
+                None => {
+                    // Every digit was a nine, so this rounds up to the next power of ten
+                    mantissa.fill(b'0');
+                    mantissa[0] = b'1';
+                    exponent += 1;
+                }
+            }
+        }
+    }
+    mantissa.resize(sig_digits, b'0');
+    let mut res = String::with_capacity(sig_digits + 22);
+    res.push(mantissa[0] as char);
+    if sig_digits > 1 {
+        res.push('.');
+        res.push_str(core::str::from_utf8(&mantissa[1..]).unwrap());
+    }
+    write!(res, "E{}", exponent).unwrap();
+    res
+}
+
+#[cfg(feature = "alloc")]
+impl<A: WordArray> Display for UnsignedInteger<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_str_radix(10))
+    }
+}
+
+/// Format an unsigned integer in a radix of `2**bits_per_digit`
+///
+/// Since each digit is just a group of bits,
+/// this extracts them directly instead of dividing.
+#[cfg(feature = "alloc")]
+fn format_unsigned_pow2_radix<A: WordArray>(value: &UnsignedInteger<A>, bits_per_digit: u32, uppercase: bool) -> String {
+    debug_assert!((1..=4).contains(&bits_per_digit));
+    let words = value.words();
+    let num_digits = value.bit_len().divide_round_up(bits_per_digit as u64).max(1);
+    let mask = (1u64 << bits_per_digit) - 1;
+    let mut res = String::with_capacity(num_digits as usize);
+    for digit_index in (0..num_digits).rev() {
+        let bit_index = digit_index * bits_per_digit as u64;
+        let word_index = (bit_index / Word::BITS) as usize;
+        let shift = bit_index % Word::BITS;
+        let mut bits = words.get(word_index).map_or(0, |word| word.0 >> shift);
+        if shift + bits_per_digit as u64 > Word::BITS {
+            // The digit straddles two words
+            bits |= words.get(word_index + 1).map_or(0, |word| word.0 << (Word::BITS - shift));
+        }
+        let digit = core::char::from_digit((bits & mask) as u32, 1 << bits_per_digit).unwrap();
+        res.push(if uppercase { digit.to_ascii_uppercase() } else { digit });
+    }
+    res
+}
+
+#[cfg(feature = "alloc")]
+impl<A: WordArray> LowerHex for UnsignedInteger<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &format_unsigned_pow2_radix(self, 4, false))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: WordArray> UpperHex for UnsignedInteger<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0x", &format_unsigned_pow2_radix(self, 4, true))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: WordArray> Octal for UnsignedInteger<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0o", &format_unsigned_pow2_radix(self, 3, false))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A: WordArray> Binary for UnsignedInteger<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "0b", &format_unsigned_pow2_radix(self, 1, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InlineWordArray;
+    use num_traits::Num;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn parse(s: &str, radix: u32) -> UnsignedInteger {
+        UnsignedInteger::from_str_radix(s, radix).unwrap()
+    }
+
+    #[test]
+    fn parse_radixes() {
+        assert_eq!(parse("1234567890123456789", 10), UnsignedInteger::from(1234567890123456789u64));
+        assert_eq!(parse("deadbeef", 16), UnsignedInteger::from(0xdead_beefu64));
+        assert_eq!(parse("101101", 2), UnsignedInteger::from(0b101101u64));
+        assert_eq!(parse("0", 10), UnsignedInteger::ZERO);
+        assert_eq!(parse("340282366920938463463374607431768211455", 10), UnsignedInteger::from(u128::MAX));
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let digits = "31415926535897932384626433832795028841971693993751058209749445923078164062862089986280348253421170679";
+        for &radix in &[2, 7, 10, 16, 36] {
+            let value = parse(digits, 10);
+            let formatted = value.to_str_radix(radix);
+            assert_eq!(parse(&formatted, radix), value, "radix {}", radix);
         }
-        res *= (digit_val as u32);
+        assert_eq!(parse(digits, 10).to_str_radix(10), digits);
     }
-    return Ok(res)
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_full_inline_array() {
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        let value: UnsignedInteger<InlineWordArray<4>> = UnsignedInteger::from_str_radix(max, 10).unwrap();
+        assert_eq!(value.words(), [Word(u64::MAX); 4]);
+        let padded = format!("{}1", "0".repeat(80));
+        let one: UnsignedInteger<InlineWordArray<4>> = UnsignedInteger::from_str_radix(&padded, 10).unwrap();
+        assert_eq!(one.words(), [Word(1)]);
+        let separated: UnsignedInteger<InlineWordArray<4>> =
+            UnsignedInteger::from_str_radix_with_separators("0_000_000_001", 10).unwrap();
+        assert_eq!(separated.words(), [Word(1)]);
+        let too_large = "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+        assert!(matches!(
+            UnsignedInteger::<InlineWordArray<4>>::from_str_radix(too_large, 10),
+            Err(ParseIntError::AllocFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_digits_full_inline_array() {
+        let mut digits = vec![0u8; 40];
+        digits.extend_from_slice(&[255; 32]);
+        let value = UnsignedInteger::<InlineWordArray<4>>::from_digits(&digits, 256).unwrap();
+        assert_eq!(value.words(), [Word(u64::MAX); 4]);
+    }
+
+    #[test]
+    fn from_str_prefixes() {
+        let parse = |s: &str| s.parse::<UnsignedInteger>();
+        assert_eq!(parse("0x1F").unwrap(), UnsignedInteger::from(31u64));
+        assert_eq!(parse("0X1f").unwrap(), UnsignedInteger::from(31u64));
+        assert_eq!(parse("0o17").unwrap(), UnsignedInteger::from(15u64));
+        assert_eq!(parse("0b101").unwrap(), UnsignedInteger::from(5u64));
+        assert_eq!(parse("1234").unwrap(), UnsignedInteger::from(1234u64));
+        assert_eq!(parse("0").unwrap(), UnsignedInteger::ZERO);
+        assert!(matches!(parse("0x"), Err(ParseIntError::EmptyString)));
+        assert!(matches!(parse("0b102"), Err(ParseIntError::InvalidDigit { digit: '2', radix: 2 })));
+    }
+
+    #[test]
+    fn from_str_whitespace_and_sign() {
+        let parse = |s: &str| s.parse::<UnsignedInteger>();
+        assert_eq!(parse(" 123 ").unwrap(), UnsignedInteger::from(123u64));
+        assert_eq!(parse("\t0x10\n").unwrap(), UnsignedInteger::from(16u64));
+        assert_eq!(parse("+0x10").unwrap(), UnsignedInteger::from(16u64));
+        assert_eq!(parse("+42").unwrap(), UnsignedInteger::from(42u64));
+        assert!(matches!(parse("-0x10"), Err(ParseIntError::ForbiddenNegative)));
+        assert!(matches!(parse("0x-10"), Err(ParseIntError::ForbiddenNegative)));
+        assert!(matches!(parse("++1"), Err(ParseIntError::InvalidDigit { digit: '+', radix: 10 })));
+        assert!(matches!(parse("0x+1"), Err(ParseIntError::InvalidDigit { digit: '+', radix: 16 })));
+        assert!(matches!(parse("1 2"), Err(ParseIntError::InvalidDigit { digit: ' ', radix: 10 })));
+        assert!(matches!(parse("  "), Err(ParseIntError::EmptyString)));
+    }
+
+    #[test]
+    fn scientific_notation() {
+        let value = parse("123456789", 10);
+        assert_eq!(value.to_scientific(4), "1.235E8");
+        assert_eq!(value.to_scientific(1), "1E8");
+        assert_eq!(value.to_scientific(9), "1.23456789E8");
+        assert_eq!(value.to_scientific(12), "1.23456789000E8");
+        assert_eq!(parse("0", 10).to_scientific(3), "0.00E0");
+        assert_eq!(parse("7", 10).to_scientific(0), "7E0");
+        // Rounding the mantissa up to ten bumps the exponent
+        assert_eq!(parse("9995", 10).to_scientific(3), "1.00E4");
+        assert_eq!(parse("9994", 10).to_scientific(3), "9.99E3");
+        assert_eq!(parse("99999", 10).to_scientific(5), "9.9999E4");
+        let huge = parse(&format!("9{}", "9".repeat(300)), 10);
+        assert_eq!(huge.to_scientific(6), "1.00000E301");
+        let power = UnsignedInteger::<Vec<Word>>::from(2u64).pow(1000).unwrap();
+        assert_eq!(power.to_scientific(5), "1.0715E301");
+    }
+
+    #[test]
+    fn decimal_exact_capacity() {
+        let mut rng = SmallRng::seed_from_u64(221);
+        for len in 1..400 {
+            let mut digits: String = (0..len).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect();
+            let value: UnsignedInteger = UnsignedInteger::from_decimal_exact(&digits).unwrap();
+            assert_eq!(value, parse(&digits, 10), "{}", digits);
+            assert_eq!(value.words.capacity(), value.words().len(), "{}", digits);
+            // All nines is the most likely to outgrow the estimate
+            digits = "9".repeat(len);
+            let value: UnsignedInteger = UnsignedInteger::from_decimal_exact(&digits).unwrap();
+            assert_eq!(value, parse(&digits, 10));
+            assert_eq!(value.words.capacity(), value.words().len(), "{}", digits);
+        }
+        // Still no slack at the largest size the benchmark parses
+        let digits: String = (0..10_000).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect();
+        let value: UnsignedInteger = UnsignedInteger::from_decimal_exact(&digits).unwrap();
+        assert_eq!(value.words.capacity(), value.words().len());
+        assert_eq!(value, parse(&digits, 10));
+        // The largest value that fits in a full inline array
+        let max: UnsignedInteger<InlineWordArray<2>> = UnsignedInteger::from_decimal_exact("+340282366920938463463374607431768211455").unwrap();
+        assert_eq!(max.words(), &[Word(u64::MAX); 2]);
+        let zero: UnsignedInteger = UnsignedInteger::from_decimal_exact("000").unwrap();
+        assert_eq!(zero, UnsignedInteger::ZERO);
+        assert!(matches!(UnsignedInteger::<Vec<Word>>::from_decimal_exact("12a"), Err(ParseIntError::InvalidDigit { digit: 'a', radix: 10 })));
+        assert!(matches!(UnsignedInteger::<Vec<Word>>::from_decimal_exact("1é"), Err(ParseIntError::InvalidDigit { digit: 'é', radix: 10 })));
+        assert!(matches!(UnsignedInteger::<Vec<Word>>::from_decimal_exact("-1"), Err(ParseIntError::ForbiddenNegative)));
+    }
+
+    #[test]
+    fn split_formatting_matches_chunks() {
+        let mut rng = SmallRng::seed_from_u64(229);
+        for &len in &[0, 1, 31, 32, 33, 64, 100, 257] {
+            let value = UnsignedInteger::from_words_iter((0..len).map(|_| Word(rng.gen()))).unwrap();
+            for &radix in &[10, 7, 16, 36] {
+                let expected = String::from_utf8(format_chunks(value.clone(), DigitChunk::new(radix), 1)).unwrap();
+                let formatted = value.to_str_radix(radix);
+                assert_eq!(formatted, expected, "{} words in base {}", len, radix);
+                assert_eq!(parse(&formatted, radix), value);
+            }
+        }
+        // Splitting at a power of the radix leaves a low half with leading zeros
+        let mut power = parse(&format!("1{}", "0".repeat(1000)), 10);
+        assert_eq!(power.to_str_radix(10), format!("1{}", "0".repeat(1000)));
+        power.add_u64(7).unwrap();
+        assert_eq!(power.to_str_radix(10), format!("1{}7", "0".repeat(999)));
+    }
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_formatting_is_identical() {
+        let mut rng = SmallRng::seed_from_u64(229);
+        for &len in &[10, 600, 3000] {
+            let value = UnsignedInteger::<Vec<Word>>::from_words_iter((0..len).map(|_| Word(rng.gen()))).unwrap();
+            for &radix in &[10, 3] {
+                assert_eq!(value.to_str_radix_parallel(radix), value.to_str_radix(radix));
+            }
+        }
+        assert_eq!(UnsignedInteger::<Vec<Word>>::ZERO.to_str_radix_parallel(10), "0");
+    }
+    #[test]
+    fn parse_bytes() {
+        let parse_bytes = |bytes: &[u8], radix| UnsignedInteger::<Vec<Word>>::parse_unsigned_radix_bytes(bytes, radix);
+        assert_eq!(parse_bytes(b"1234567890123456789", 10).unwrap(), parse("1234567890123456789", 10));
+        assert_eq!(parse_bytes(b"+ff", 16).unwrap(), UnsignedInteger::from(255u64));
+        // Embedded invalid bytes, both ASCII and not
+        assert!(matches!(parse_bytes(b"12x4", 10), Err(ParseIntError::InvalidDigit { digit: 'x', radix: 10 })));
+        assert!(matches!(
+            parse_bytes(b"12\xff34", 10),
+            Err(ParseIntError::InvalidDigit { digit: char::REPLACEMENT_CHARACTER, radix: 10 })
+        ));
+        assert!(matches!(parse_bytes(b"12\x0034", 10), Err(ParseIntError::InvalidDigit { digit: '\0', radix: 10 })));
+        assert!(matches!(parse_bytes(b"-12", 10), Err(ParseIntError::ForbiddenNegative)));
+        assert!(matches!(parse_bytes(b"", 10), Err(ParseIntError::EmptyString)));
+    }
+    #[test]
+    fn from_str_radix_whitespace() {
+        let parse = |s: &str, radix| UnsignedInteger::<Vec<Word>>::from_str_radix(s, radix);
+        assert_eq!(parse("  ff", 16).unwrap(), UnsignedInteger::from(255u64));
+        assert_eq!(parse("ff\r\n", 16).unwrap(), UnsignedInteger::from(255u64));
+        assert_eq!(parse("\t101 ", 2).unwrap(), UnsignedInteger::from(5u64));
+        assert!(matches!(parse("f f", 16), Err(ParseIntError::InvalidDigit { digit: ' ', radix: 16 })));
+        assert!(matches!(parse("1\t0", 2), Err(ParseIntError::InvalidDigit { digit: '\t', radix: 2 })));
+        assert!(matches!(" 1 2 ".parse::<UnsignedInteger>(), Err(ParseIntError::InvalidDigit { digit: ' ', radix: 10 })));
+    }
+    #[test]
+    fn parse_letter_digits() {
+        for s in &["ff", "FF", "Ff", "fF"] {
+            assert_eq!(parse(s, 16), UnsignedInteger::from(255u64));
+        }
+        assert_eq!(parse("aA", 16), UnsignedInteger::from(0xaau64));
+        assert_eq!(parse("zZ", 36), UnsignedInteger::from(35 * 36 + 35u64));
+        assert!(matches!(
+            UnsignedInteger::<Vec<Word>>::from_str_radix("fg", 16),
+            Err(ParseIntError::InvalidDigit { digit: 'g', radix: 16 })
+        ));
+        assert!(matches!(
+            UnsignedInteger::<Vec<Word>>::from_str_radix("1A", 10),
+            Err(ParseIntError::InvalidDigit { digit: 'A', radix: 10 })
+        ));
+    }
+    #[test]
+    fn display_round_trip() {
+        let digits = "9876543210".repeat(20);
+        assert_eq!(digits.len(), 200);
+        let value = parse(&digits, 10);
+        assert_eq!(value.to_string(), digits);
+        assert_eq!(format!("{:>205}", value), format!("     {}", digits));
+        assert_eq!(UnsignedInteger::<Vec<Word>>::ZERO.to_string(), "0");
+        assert_eq!(UnsignedInteger::<Vec<Word>>::ZERO.to_str_radix(36), "0");
+        assert_eq!(UnsignedInteger::<Vec<Word>>::from(35u64).to_str_radix(36), "z");
+    }
+    #[test]
+    fn pow2_radix_formatting_matches_u64() {
+        let mut rng = SmallRng::seed_from_u64(258);
+        let samples = [0, 1, 7, 8, 255, 1 << 63, u64::MAX].iter().copied()
+            .chain((0..100).map(|_| rng.gen::<u64>() >> rng.gen_range(0..64)));
+        for value in samples {
+            let big = UnsignedInteger::<Vec<Word>>::from(value);
+            assert_eq!(format!("{:x}", big), format!("{:x}", value));
+            assert_eq!(format!("{:X}", big), format!("{:X}", value));
+            assert_eq!(format!("{:o}", big), format!("{:o}", value));
+            assert_eq!(format!("{:b}", big), format!("{:b}", value));
+            assert_eq!(format!("{:#x}", big), format!("{:#x}", value));
+            assert_eq!(format!("{:#o}", big), format!("{:#o}", value));
+            assert_eq!(format!("{:#b}", big), format!("{:#b}", value));
+            assert_eq!(format!("{:#018x}", big), format!("{:#018x}", value));
+        }
+        // Digits straddling a word boundary, and zero padding inside the lower word
+        let wide = UnsignedInteger::<Vec<Word>>::from(u128::MAX / 3);
+        assert_eq!(format!("{:o}", wide), format!("{:o}", u128::MAX / 3));
+        assert_eq!(format!("{:x}", UnsignedInteger::<Vec<Word>>::from(1u128 << 64)), format!("{:x}", 1u128 << 64));
+    }
+    #[test]
+    fn digit_separators() {
+        let parse = |s: &str, radix| UnsignedInteger::<Vec<Word>>::from_str_radix_with_separators(s, radix);
+        assert_eq!(parse("1_234_567", 10).unwrap(), UnsignedInteger::from(1_234_567u64));
+        assert_eq!(parse("dead_beef", 16).unwrap(), UnsignedInteger::from(0xdead_beefu64));
+        assert_eq!(parse("1_0_1", 2).unwrap(), UnsignedInteger::from(5u64));
+        assert_eq!(parse("+1_000", 10).unwrap(), UnsignedInteger::from(1000u64));
+        assert_eq!(parse("42", 10).unwrap(), UnsignedInteger::from(42u64));
+        for malformed in &["_1", "1_", "1__0", "_", "+_1"] {
+            assert!(
+                matches!(parse(malformed, 10), Err(ParseIntError::InvalidDigit { digit: '_', radix: 10 })),
+                "{:?}", malformed
+            );
+        }
+        // Without opting in, underscores are just invalid digits
+        assert!(matches!(
+            UnsignedInteger::<Vec<Word>>::from_str_radix("1_000", 10),
+            Err(ParseIntError::InvalidDigit { digit: '_', radix: 10 })
+        ));
+    }
+    #[test]
+    fn hex_matches_radix_parser() {
+        let mut rng = SmallRng::seed_from_u64(300);
+        for len in (1..=70).chain(Some(1000)) {
+            let digits: String = (0..len)
+                .map(|_| {
+                    let digit = core::char::from_digit(rng.gen_range(0..16), 16).unwrap();
+                    if rng.gen() { digit.to_ascii_uppercase() } else { digit }
+                })
+                .collect();
+            let fast = UnsignedInteger::<Vec<Word>>::from_hex_str(&digits).unwrap();
+            assert_eq!(fast, parse(&digits, 16), "{}", digits);
+            assert_eq!(fast.check_invariants(), Ok(()));
+        }
+        assert!(UnsignedInteger::<Vec<Word>>::from_hex_str("000").unwrap().is_empty());
+        assert!(UnsignedInteger::<InlineWordArray<0>>::from_hex_str("+0000").unwrap().is_empty());
+        // Zero padding doesn't count against a fixed capacity, just like the radix parser
+        let padded = format!("000{}", "f".repeat(32));
+        let inline = UnsignedInteger::<InlineWordArray<2>>::from_hex_str(&padded).unwrap();
+        assert_eq!(inline.words(), &[Word(u64::MAX); 2]);
+        assert_eq!(inline, UnsignedInteger::from_str_radix(&padded, 16).unwrap());
+        assert!(matches!(
+            UnsignedInteger::<InlineWordArray<2>>::from_hex_str(&format!("1{}", "0".repeat(32))),
+            Err(ParseIntError::AllocFailed { .. })
+        ));
+        assert!(matches!(
+            UnsignedInteger::<Vec<Word>>::from_hex_str("12g4"),
+            Err(ParseIntError::InvalidDigit { digit: 'g', radix: 16 })
+        ));
+        assert!(matches!(UnsignedInteger::<Vec<Word>>::from_hex_str(""), Err(ParseIntError::EmptyString)));
+    }
+    #[test]
+    fn format_thousand_digits() {
+        let mut rng = SmallRng::seed_from_u64(303);
+        let mut digits: String = (0..1000).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect();
+        digits.replace_range(..1, "7");
+        let value = parse(&digits, 10);
+        assert_eq!(value.to_str_radix(10), digits);
+        for radix in 2..=36 {
+            let formatted = value.to_str_radix(radix);
+            assert!(!formatted.starts_with('0'));
+            assert!(formatted.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()));
+            assert_eq!(parse(&formatted, radix), value, "base {}", radix);
+        }
+        // Powers of two format directly from the bits, so they cross-check the division path
+        assert_eq!(value.to_str_radix(16), format!("{:x}", value));
+    }
+}
@@ -0,0 +1,61 @@
+//! Constant-time operations using subtle
+use subtle::{Choice, ConstantTimeEq};
+
+use crate::memory::WordArray;
+use crate::uint::UnsignedInteger;
+
+impl<A: WordArray> UnsignedInteger<A> {
+    /// Check if this integer equals the specified integer in constant time
+    ///
+    /// Unlike [PartialEq], this never exits early,
+    /// so the timing doesn't reveal where the integers differ.
+    /// The shorter integer is padded with zero words up to the larger capacity,
+    /// so integers in buffers of the same capacity take the same time
+    /// regardless of their lengths.
+    pub fn ct_eq(&self, other: &Self) -> Choice {
+        let (words, other_words) = (self.words(), other.words());
+        let padded_len = self.as_word_array().capacity()
+            .max(other.as_word_array().capacity());
+        let mut res = Choice::from(1);
+        for index in 0..padded_len {
+            let word = words.get(index).map_or(0, |word| word.0);
+            let other_word = other_words.get(index).map_or(0, |word| word.0);
+            res &= word.ct_eq(&other_word);
+        }
+        res
+    }
+}
+impl<A: WordArray> ConstantTimeEq for UnsignedInteger<A> {
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> Choice {
+        UnsignedInteger::ct_eq(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{InlineWordArray, Word};
+
+    #[test]
+    fn ct_eq_agrees_with_eq() {
+        let values: Vec<UnsignedInteger> = vec![
+            UnsignedInteger::ZERO,
+            1u64.into(),
+            u64::MAX.into(),
+            u128::MAX.into(),
+            (1u128 << 64).into(),
+            UnsignedInteger::from_words_iter([Word(1), Word(0), Word(1)].iter().copied()).unwrap()
+        ];
+        for left in &values {
+            for right in &values {
+                assert_eq!(bool::from(left.ct_eq(right)), left == right, "{:?} == {:?}", left, right);
+            }
+        }
+        // Differing lengths within the same capacity
+        let short = UnsignedInteger::<InlineWordArray<4>>::try_from_u128(5).unwrap();
+        let long = UnsignedInteger::<InlineWordArray<4>>::try_from_u128(5 | 1 << 64).unwrap();
+        assert!(!bool::from(short.ct_eq(&long)));
+        assert!(bool::from(ConstantTimeEq::ct_eq(&short, &short.clone())));
+    }
+}
@@ -1,24 +1,362 @@
 //! Unsigned integers
 use num_traits::{Num, Zero, One};
+use thiserror::Error;
 
 use crate::string::{ParseIntError};
-use crate::memory::{WordArray, Word, IAllocError};
-use std::ops::Add;
+use crate::memory::{WordArray, Word, IAllocError, CapacityExceeded, Endian};
+use crate::arith_utils::ArithUtil;
+use crate::montgomery::{MontgomeryCtx, MontgomeryError};
+use core::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Div, Rem, Shl, ShlAssign, Shr, ShrAssign, BitAnd, BitOr, BitXor};
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::fmt::{self, Debug, Formatter};
+use core::convert::TryFrom;
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 
+/// An error indicating that an array of words isn't normalized,
+/// because its most significant word is zero
+#[derive(Error, Debug, Copy, Clone, Eq, PartialEq)]
+#[error("Most significant of {len} words is zero")]
+pub struct NotNormalized {
+    /// The length of the offending array
+    pub len: usize
+}
+
+/// A violation of the invariants of an [UnsignedInteger]
+#[derive(Error, Debug, Copy, Clone, Eq, PartialEq)]
+pub enum InvariantViolation {
+    /// The most significant word is zero
+    #[error("Trailing zero word at index {index}")]
+    TrailingZeroWord {
+        /// The index of the most significant word
+        index: usize
+    },
+    /// The [WordArray] reports a length larger than its capacity
+    #[error("Length {len} exceeds capacity {capacity}")]
+    LengthExceedsCapacity {
+        /// The length of the array
+        len: usize,
+        /// The capacity of the array
+        capacity: usize
+    },
+    /// The [WordArray] reports a length different from its slice of words
+    #[error("Length {len} doesn't match slice length {slice_len}")]
+    SliceLengthMismatch {
+        /// The length of the array
+        len: usize,
+        /// The length of the slice of words
+        slice_len: usize
+    }
+}
+
+/// An error indicating that an integer is too large
+/// to convert into a primitive type
+#[derive(Error, Debug, Copy, Clone, Eq, PartialEq)]
+#[error("Integer is too large for the target type")]
+pub struct TooLarge;
+
+/// An error that occurs subtracting unsigned integers
+#[derive(Error, Debug)]
+pub enum SubError<A: IAllocError> {
+    /// The result would have been negative
+    #[error("Subtraction underflowed")]
+    Underflow,
+    /// Allocating space failed
+    #[error("Allocation failed: {cause}")]
+    AllocFailed {
+        /// The underlying allocation error
+        #[from]
+        cause: A
+    }
+}
+
+/// An error that occurs dividing unsigned integers
+#[derive(Error, Debug)]
+pub enum DivError<A: IAllocError> {
+    /// The divisor was zero
+    #[error("Division by zero")]
+    DivisionByZero,
+    /// Allocating space failed
+    #[error("Allocation failed: {cause}")]
+    AllocFailed {
+        /// The underlying allocation error
+        #[from]
+        cause: A
+    }
+}
+
+/// An error that occurs during modular exponentiation
+#[derive(Error, Debug)]
+pub enum PowError<A: IAllocError> {
+    /// The modulus was zero
+    #[error("Zero modulus")]
+    ZeroModulus,
+    /// The exponent needed more squarings than allowed
+    ///
+    /// See [UnsignedInteger::modpow_limited]
+    #[error("Exponent of {bits} bits exceeds the limit of {max_squarings} squarings")]
+    ExponentTooLarge {
+        /// The bit length of the exponent
+        bits: u64,
+        /// The maximum number of squarings allowed
+        max_squarings: u64
+    },
+    /// Allocating space failed
+    #[error("Allocation failed: {cause}")]
+    AllocFailed {
+        /// The underlying allocation error
+        #[from]
+        cause: A
+    }
+}
+impl<A: IAllocError> From<DivError<A>> for PowError<A> {
+    #[inline]
+    fn from(cause: DivError<A>) -> Self {
+        match cause {
+            DivError::DivisionByZero => PowError::ZeroModulus,
+            DivError::AllocFailed { cause } => PowError::AllocFailed { cause }
+        }
+    }
+}
 
 /// An unsigned integer
 ///
 /// Memory is managed via the
 /// specified [ArrayType]
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
 pub struct UnsignedInteger<A: WordArray = Vec<Word>> {
     /// The internal array of words
     pub(crate) words: A
 }
+/// An unsigned integer
+///
+/// Memory is managed via the
+/// specified [ArrayType]
+#[cfg(not(feature = "alloc"))]
+#[derive(Clone)]
+pub struct UnsignedInteger<A: WordArray> {
+    /// The internal array of words
+    pub(crate) words: A
+}
+
+/// Reusable scratch space for [UnsignedInteger::div_rem_with]
+///
+/// Long division needs normalized copies of both operands.
+/// Dividing repeatedly through the same context reuses
+/// their buffers, which only grow as needed.
+pub struct DivContext<A: WordArray> {
+    /// The normalized dividend, which becomes the remainder
+    remainder: UnsignedInteger<A>,
+    /// The normalized divisor
+    divisor: UnsignedInteger<A>
+}
+impl<A: WordArray> DivContext<A> {
+    /// Create a new context, without allocating anything
+    #[inline]
+    pub const fn new() -> Self {
+        DivContext {
+            remainder: UnsignedInteger::ZERO,
+            divisor: UnsignedInteger::ZERO
+        }
+    }
+}
+impl<A: WordArray> Default for DivContext<A> {
+    #[inline]
+    fn default() -> Self {
+        DivContext::new()
+    }
+}
+impl<A: WordArray> Debug for UnsignedInteger<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Formatting the words directly avoids needing to allocate
+        f.write_str("UnsignedInteger(0x")?;
+        let mut words = self.words_be();
+        match words.next() {
+            Some(top) => write!(f, "{:x}", top.0)?,
+            None => f.write_str("0")?
+        }
+        for word in words {
+            write!(f, "{:016x}", word.0)?;
+        }
+        f.write_str(")")
+    }
+}
 impl<A: WordArray> Add for UnsignedInteger<A> {
     type Output = Self;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        self.add(&rhs).unwrap()
+    #[inline]
+    fn add(mut self, rhs: Self) -> Self::Output {
+        UnsignedInteger::add(&mut self, &rhs).unwrap();
+        self
+    }
+}
+impl<A: WordArray<AllocErr = !>> AddAssign for UnsignedInteger<A> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        match UnsignedInteger::add(self, &rhs) {
+            Ok(()) => {},
+            Err(never) => never
+        }
+    }
+}
+impl<A: WordArray> Sub for UnsignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        UnsignedInteger::sub(&mut self, &rhs).unwrap();
+        self
+    }
+}
+impl<'a, A: WordArray<AllocErr = !>> AddAssign<&'a Self> for UnsignedInteger<A> {
+    #[inline]
+    fn add_assign(&mut self, rhs: &'a Self) {
+        match UnsignedInteger::add(self, rhs) {
+            Ok(()) => {},
+            Err(never) => never
+        }
+    }
+}
+impl<'a, A: WordArray<AllocErr = !>> SubAssign<&'a Self> for UnsignedInteger<A> {
+    /// Panics if the other integer is larger,
+    /// since unsigned subtraction would underflow
+    #[inline]
+    fn sub_assign(&mut self, rhs: &'a Self) {
+        if let Err(cause) = UnsignedInteger::sub(self, rhs) {
+            panic!("{}", cause);
+        }
+    }
+}
+impl<A: WordArray> Mul for UnsignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        UnsignedInteger::mul(&self, &rhs).unwrap()
+    }
+}
+impl<A: WordArray> MulAssign for UnsignedInteger<A> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = UnsignedInteger::mul(self, &rhs).unwrap();
+    }
+}
+impl<'a, A: WordArray<AllocErr = !>> MulAssign<&'a Self> for UnsignedInteger<A> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: &'a Self) {
+        *self = match UnsignedInteger::mul(self, rhs) {
+            Ok(product) => product,
+            Err(never) => never
+        };
+    }
+}
+impl<A: WordArray> Div for UnsignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).unwrap().0
+    }
+}
+impl<A: WordArray> Rem for UnsignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.div_rem(&rhs).unwrap().1
+    }
+}
+impl<A: WordArray> BitAnd for UnsignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(mut self, rhs: Self) -> Self::Output {
+        // Words past the end of the shorter operand are always zero
+        self.words.truncate(rhs.words().len());
+        for (word, other) in self.words.as_mut().iter_mut().zip(rhs.words()) {
+            word.0 &= other.0;
+        }
+        self.trim();
+        self
+    }
+}
+impl<A: WordArray> BitOr for UnsignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self.bitwise_assign(&rhs, |left, right| left | right).unwrap();
+        self
+    }
+}
+impl<A: WordArray> BitXor for UnsignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn bitxor(mut self, rhs: Self) -> Self::Output {
+        self.bitwise_assign(&rhs, |left, right| left ^ right).unwrap();
+        self
+    }
+}
+impl<A: WordArray> Shl<u32> for UnsignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn shl(mut self, bits: u32) -> Self::Output {
+        UnsignedInteger::shl_assign(&mut self, bits).unwrap();
+        self
+    }
+}
+impl<A: WordArray> ShlAssign<u32> for UnsignedInteger<A> {
+    #[inline]
+    fn shl_assign(&mut self, bits: u32) {
+        UnsignedInteger::shl_assign(self, bits).unwrap();
+    }
+}
+impl<A: WordArray> Shr<u32> for UnsignedInteger<A> {
+    type Output = Self;
+
+    #[inline]
+    fn shr(mut self, bits: u32) -> Self::Output {
+        UnsignedInteger::shr_assign(&mut self, bits);
+        self
+    }
+}
+impl<A: WordArray> ShrAssign<u32> for UnsignedInteger<A> {
+    #[inline]
+    fn shr_assign(&mut self, bits: u32) {
+        UnsignedInteger::shr_assign(self, bits);
+    }
+}
+impl<A: WordArray> PartialEq for UnsignedInteger<A> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        // Normalization guarantees each value has a unique representation
+        self.words() == other.words()
+    }
+}
+impl<A: WordArray> Eq for UnsignedInteger<A> {}
+impl<A: WordArray> Hash for UnsignedInteger<A> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Normalization makes the words canonical, consistent with `Eq`
+        self.words().hash(state);
+    }
+}
+impl<A: WordArray> PartialOrd for UnsignedInteger<A> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<A: WordArray> Ord for UnsignedInteger<A> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_magnitude(other)
     }
 }
 impl<A: WordArray> Zero for UnsignedInteger<A> {
@@ -34,28 +372,61 @@ impl<A: WordArray> Zero for UnsignedInteger<A> {
 
     #[inline]
     fn is_zero(&self) -> bool {
-        #[cfg(debug_assertions)] {
-            if self.words.len() > 0 {
-                debug_assert!(
-                    self.words().iter().all(|word| word.0.is_zero())
-                )
-            }
-        }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
         self.words.len() == 0
     }
 }
-impl<A: WordArray> One for UnsignedInteger {
+impl<A: WordArray> One for UnsignedInteger<A> {
     #[inline]
     fn one() -> Self {
-        let mut res = Self::ZER;
-        res.set(1).unwrap();
-        res
+        UnsignedInteger::one().unwrap()
+    }
+
+    #[inline]
+    fn set_one(&mut self) {
+        UnsignedInteger::set_one(self).unwrap();
     }
 
     #[inline]
     fn is_one(&self) -> bool where
         Self: PartialEq, {
-        self.words.len() == 1 && self.words[0] == 1
+        self.words() == [Word(1)]
+    }
+}
+macro_rules! impl_from_primitive {
+    ($($target:ty),*) => {$(
+        impl<A: WordArray<AllocErr = !>> From<$target> for UnsignedInteger<A> {
+            #[inline]
+            fn from(val: $target) -> Self {
+                let mut res = Self::ZERO;
+                match res.set(val as u64) {
+                    Ok(()) => res,
+                    Err(never) => never
+                }
+            }
+        }
+    )*};
+}
+impl_from_primitive!(u8, u16, u32, u64, usize);
+impl<A: WordArray> TryFrom<&UnsignedInteger<A>> for u64 {
+    type Error = TooLarge;
+
+    #[inline]
+    fn try_from(val: &UnsignedInteger<A>) -> Result<u64, TooLarge> {
+        match *val.words() {
+            [] => Ok(0),
+            [Word(word)] => Ok(word),
+            _ => Err(TooLarge)
+        }
+    }
+}
+impl<A: WordArray<AllocErr = !>> From<u128> for UnsignedInteger<A> {
+    #[inline]
+    fn from(val: u128) -> Self {
+        match Self::try_from_u128(val) {
+            Ok(res) => res,
+            Err(never) => never
+        }
     }
 }
 impl<A: WordArray> UnsignedInteger<A> {
@@ -66,145 +437,3829 @@ impl<A: WordArray> UnsignedInteger<A> {
     pub fn words(&self) -> &[Word] {
         self.words.as_ref()
     }
+    /// Split the words at the specified index,
+    /// returning the low `at` words and the remaining high words
+    ///
+    /// The index is clamped to the length, so the high words may be empty.
+    /// This gives the halves `low + high * 2**(64 * at)`
+    /// used by divide-and-conquer algorithms, without copying.
+    /// Only the high words are normalized, since the low words
+    /// may have zeros on top.
+    #[inline]
+    pub fn split_words(&self, at: usize) -> (&[Word], &[Word]) {
+        self.words().split_at(at.min(self.words.len()))
+    }
+    /// The number of words in this integer
+    ///
+    /// Since integers are always normalized,
+    /// this is the minimum number of words needed to hold the value.
+    ///
+    /// ```
+    /// # use duckmp::uint::UnsignedInteger;
+    /// let small: UnsignedInteger = 7u64.into();
+    /// assert_eq!(small.len(), 1);
+    /// let large: UnsignedInteger = (1u128 << 64).into();
+    /// assert_eq!(large.len(), 2);
+    /// assert_eq!(UnsignedInteger::<Vec<_>>::ZERO.len(), 0);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+    /// Check if this integer has no words,
+    /// which is equivalent to being zero
+    ///
+    /// ```
+    /// # use duckmp::uint::UnsignedInteger;
+    /// assert!(UnsignedInteger::<Vec<_>>::ZERO.is_empty());
+    /// let one: UnsignedInteger = 1u64.into();
+    /// assert!(!one.is_empty());
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+    /// Iterate over the words from most significant to least
+    ///
+    /// This is the reverse of the little-endian order of [UnsignedInteger::words],
+    /// without needing to allocate a reversed copy.
+    #[inline]
+    pub fn words_be(&self) -> impl DoubleEndedIterator<Item = Word> + ExactSizeIterator + '_ {
+        self.words().iter().rev().copied()
+    }
     /// A mutable slice of words
     #[inline]
     pub fn words_mut(&mut self) -> &mut [Word] {
         self.words.as_mut()
     }
-    /// Set the integer equal to the specified (primitive) value
+    /// The number of significant bits in this integer
+    ///
+    /// This is zero for [UnsignedInteger::ZERO].
     #[inline]
-    pub fn set(&mut self, val: u64) -> Result<(), A::AllocErr> {
-        if val > 0 {
-            self.words.reserve(1)?;
-        }
-        self.words.clear();
-        if val > 0 {
-            unsafe { self.words.push_unchecked(val) };
+    pub fn bit_len(&self) -> u64 {
+        bit_length(self.words())
+    }
+    /// The number of one bits in this integer
+    #[inline]
+    pub fn count_ones(&self) -> u64 {
+        self.words().iter().map(|word| word.0.count_ones() as u64).sum()
+    }
+    /// Check if this integer is a power of two,
+    /// meaning exactly one bit is set
+    ///
+    /// Zero is not a power of two.
+    #[inline]
+    pub fn is_power_of_two(&self) -> bool {
+        match self.words().split_last() {
+            Some((top, rest)) => top.0.is_power_of_two() && rest.iter().all(|word| word.0 == 0),
+            None => false
         }
-        Ok(())
     }
-    /// Create an integer from an array of words
+    /// If this integer is `2**exponent`, return the exponent
+    ///
+    /// Exponents too large for a shift amount give `None`,
+    /// falling back to the general algorithms.
     #[inline]
-    pub fn from_word_array(words: A) -> Self {
-        UnsignedInteger { words }
+    fn power_of_two_exponent(&self) -> Option<u32> {
+        if self.is_power_of_two() {
+            u32::try_from(self.trailing_zeros()?).ok()
+        } else {
+            None
+        }
     }
-    /// Get the underlying array of words
+    /// The number of trailing zero bits in this integer
+    ///
+    /// Returns `None` for zero, which has infinitely many.
     #[inline]
-    pub fn as_word_array(&self) -> &A {
-        &self.words
+    pub fn trailing_zeros(&self) -> Option<u64> {
+        let index = self.words().iter().position(|word| word.0 != 0)?;
+        Some(index as u64 * Word::BITS + self.words()[index].0.trailing_zeros() as u64)
     }
-    /// Attempt to add the specified integer to this integer
+    /// Check if the bit at the specified index is set,
+    /// counting from the least significant bit
     ///
-    /// Errors if allocating space fails
+    /// Bits past the [bit length](UnsignedInteger::bit_len) are always zero.
     #[inline]
-    pub fn add(&mut self, other: &Self) -> Result<(), A::AllocErr> {
-        self.words.reserve(self.len().max(other.len())
-            .checked_add(1)
-            .ok_or_else(A::AllocErr::capacity_arithmetic_overflow)?)?;
-        self.unchecked_add(other);
-        Ok(())
+    pub fn get_bit(&self, index: u64) -> bool {
+        let word_index = index / Word::BITS;
+        if word_index >= self.words().len() as u64 {
+            return false;
+        }
+        (self.words()[word_index as usize].0 >> (index % Word::BITS)) & 1 == 1
     }
-    /// Add the specified integer to this integer,
-    /// without checking for overflow
+    /// Set or clear the bit at the specified index,
+    /// counting from the least significant bit
     ///
-    /// ## Safety
-    /// Assumes `self.words.capacity >= max(self.len, other.len) + 1`
-    pub fn unchecked_add(&mut self, other: &Self) {
-        /*
-         * Grade school addition algorithm:
-         * For example,
-         * 1 2 3
-         *   4 5
-         * ------
-         * 1 6 7
-         *
-         * Just iterate over each corresponding index,
-         * propagating carries as needed.
-         */
-        let mut carry = false;
-        #[inline]
-        unsafe fn ensure_iter<A: WordArray>(words: &mut A, target_index: usize) -> &mut Word {
-            if target_index >= words.len() {
-                /*
-                 * Worst case, we should only need to append a single word
-                 * to the array. That is because we also did the necessary
-                 * bounds check last index as well. For example, if at the start of the call,
-                 * `self.len() == 2, other.len() == 4`, when we are at iteration #4 of 'other',
-                 * we can rely on the fact that iteration #3 would've already expanded `self.words` up
-                 * to length three (so we would only need to add one more word, not two).
-                 */
-                debug_assert_eq!(
-                    target_index + 1,
-                    words.len()
-                );
-                unsafe {
-                    words.unchecked_push(Word(0));
+    /// Setting a bit past the [bit length](UnsignedInteger::bit_len) grows this integer,
+    /// while clearing the top bit shrinks it.
+    ///
+    /// Errors if allocating space fails
+    pub fn set_bit(&mut self, index: u64, value: bool) -> Result<(), A::AllocErr> {
+        let word_index = usize::try_from(index / Word::BITS)
+            .map_err(|_| A::AllocErr::capacity_arithmetic_overflow())?;
+        let mask = 1 << (index % Word::BITS);
+        let len = self.words.len();
+        if value {
+            if word_index >= len {
+                self.words.reserve(word_index + 1 - len)?;
+                for _ in len..=word_index {
+                    unsafe { self.words.unchecked_push(Word(0)) };
                 }
             }
-            debug_assert!(target_index < words.len());
-            &mut words.get_unchecked_mut(target_index).0
-        }
-        for (addend_index, addend) in other.words().iter().enumerate() {
-            let target_word = unsafe { ensure_iter(&mut self.words, addend_index) };
-            let (addend, new_carry) = addend.0.overflowing_add(carry as u64);
-            carry = new_carry;
-            let (res, new_carry) = target_word.0.overflowing_add(addend);
-            debug_assert!(!carry, "Double carry");
-            carry = new_carry;
-            target_word.0 = res;
-        }
-        {
-            // Add final carry
-            let target_index = other.words.len();
-            let target_word = unsafe { ensure_iter(&mut self.words, target_index) };
-            let (res, new_carry) = (*target_word).overflowing_add(carry as u64);
-            target_word.0 = res;
-            if new_carry {
-                debug_assert!(self.words.capacity() >= target_index + 1);
-                unsafe { self.words.unchecked_push(Word(1)) };
-            }
+            self.words_mut()[word_index].0 |= mask;
+        } else if word_index < len {
+            self.words_mut()[word_index].0 &= !mask;
+            self.trim();
         }
-        debug_assert_ne!(self.words.last(), Some(Word(0)));
-    }
-    /// Add the specified [u64] to this integer
-    #[inline]
-    pub fn add_u64(&mut self, val: u64) -> Result<(), A::AllocErr> {
-        self.words.reserve(1)?;
-        unsafe { self.unchecked_add_u64(val) }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
         Ok(())
     }
-    /// Add the specified [u64] to this integer,
-    /// without checking for the right capacity.
+    /// Check if this integer is even
     ///
-    /// ## Safety
-    /// Assumes `self.words.capacity >= self.words.len + 1`,
-    /// as if calling `self.words.reserve(1)`
-    pub unsafe fn unchecked_add_u64(&mut self, mut val: u64) {
-        if val == 0 { return }
-        for target_word in self.words_mut().iter_mut() {
-            let (res, carry) = target_word.0.overflowing_add(val);
-            target_word.0 = res;
-            if carry {
-                val = 1;
-                continue;
-            } else {
-                break;
-            }
-        }
-        if val > 0 {
-            unsafe {
-                self.words.unchecked_push(Word(val));
-            }
-        }
-        debug_assert_ne!(self.words.last(), Some(Word(0)));
-
+    /// Zero is even.
+    #[inline]
+    pub fn is_even(&self) -> bool {
+        !self.is_odd()
     }
-}
-impl<A: WordArray> Num for UnsignedInteger<A> {
-    type FromStrRadixErr = ParseIntError<A::AllocErr>;
-    /// Parse a string in the specified radix (base)
+    /// Check if this integer is odd
     #[inline]
-    fn from_str_radix(str: &str, radix: u32) -> Result<Self, ParseIntError<A::AllocErr>> {
-        crate::string::parse_unsigned_radix(str, radix)
+    pub fn is_odd(&self) -> bool {
+        self.words().first().map_or(0, |word| word.0) & 1 == 1
     }
-}
\ No newline at end of file
+    /// The approximate number of bytes of heap memory owned by this integer
+    ///
+    /// See [WordArray::heap_bytes]
+    #[inline]
+    pub fn heap_bytes(&self) -> usize {
+        self.words.heap_bytes()
+    }
+    /// Release any excess capacity held by the underlying array
+    ///
+    /// This is useful for long-lived integers,
+    /// after a computation that needed more space than the result.
+    ///
+    /// See [WordArray::shrink_to_fit]
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.words.shrink_to_fit();
+    }
+    /// Set the integer equal to the specified (primitive) value
+    #[inline]
+    pub fn set(&mut self, val: u64) -> Result<(), A::AllocErr> {
+        // Clearing frees up any existing words, so only an empty array needs to grow
+        if val > 0 && self.words.capacity() == 0 {
+            self.words.reserve(1)?;
+        }
+        self.words.clear();
+        if val > 0 {
+            unsafe { self.words.unchecked_push(Word(val)) };
+        }
+        Ok(())
+    }
+    /// Create an integer equal to the specified [u128]
+    ///
+    /// For arrays that can't fail to allocate, prefer the [From] impl.
+    ///
+    /// Errors if allocating space fails
+    pub fn try_from_u128(val: u128) -> Result<Self, A::AllocErr> {
+        let (low, high) = (val as u64, (val >> 64) as u64);
+        let len = if high != 0 { 2 } else if low != 0 { 1 } else { 0 };
+        let mut words = A::with_capacity(len)?;
+        unsafe {
+            if len >= 1 {
+                words.unchecked_push(Word(low));
+            }
+            if len == 2 {
+                words.unchecked_push(Word(high));
+            }
+        }
+        Ok(UnsignedInteger { words })
+    }
+    /// Convert this integer into a [u128],
+    /// returning `None` if it needs more than two words
+    #[inline]
+    pub fn to_u128(&self) -> Option<u128> {
+        match *self.words() {
+            [] => Some(0),
+            [Word(low)] => Some(low as u128),
+            [Word(low), Word(high)] => Some(((high as u128) << 64) | (low as u128)),
+            _ => None
+        }
+    }
+    /// Convert this integer to the nearest [f64]
+    ///
+    /// This rounds to nearest (ties to even), like `u64 as f64`.
+    /// Values too large for a finite float give infinity.
+    pub fn to_f64(&self) -> f64 {
+        let words = self.words();
+        let bits = self.bit_len();
+        if bits <= Word::BITS {
+            return words.first().map_or(0.0, |word| word.0 as f64);
+        } else if bits > 1024 {
+            // At least `2**1024`, which is past the largest finite float
+            return f64::INFINITY;
+        }
+        /*
+         * Take the top 64 bits, and fold every bit below them into the lowest one.
+         * That leaves 11 bits below the 53-bit mantissa, so the conversion
+         * rounds exactly as if it had seen all the bits (the lowest is a sticky bit).
+         */
+        let low_bit = bits - Word::BITS;
+        let (index, shift) = ((low_bit / Word::BITS) as usize, (low_bit % Word::BITS) as u32);
+        let mut top = words[index].0 >> shift;
+        if shift != 0 {
+            top |= words[index + 1].0 << (64 - shift);
+        }
+        let sticky = words[..index].iter().any(|word| word.0 != 0)
+            || words[index].0 & ((1 << shift) - 1) != 0;
+        // Scaling by a power of two is exact, unless it overflows to infinity
+        let scale = f64::from_bits((low_bit + 1023) << 52);
+        (top | sticky as u64) as f64 * scale
+    }
+    /// Create an integer equal to one
+    ///
+    /// Unlike [ZERO](UnsignedInteger::ZERO), this can't be a constant,
+    /// since one needs an allocated word.
+    /// For arrays that can't fail to allocate, the [One] impl is equivalent.
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn one() -> Result<Self, A::AllocErr> {
+        let mut res = Self::ZERO;
+        res.set_one()?;
+        Ok(res)
+    }
+    /// Set the integer equal to one
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn set_one(&mut self) -> Result<(), A::AllocErr> {
+        self.words.clear();
+        self.words.reserve(1)?;
+        unsafe { self.words.unchecked_push(Word(1)) };
+        Ok(())
+    }
+    /// Create an integer from an array of words,
+    /// trusting that it is already normalized
+    ///
+    /// This is a trust boundary: The words must be little-endian,
+    /// and the most significant word must be nonzero (or the array empty).
+    /// Nothing checks this, and passing trailing zero words
+    /// silently breaks comparison, formatting and [Zero::is_zero].
+    /// Prefer [UnsignedInteger::try_from_word_array] for externally produced words.
+    #[inline]
+    pub fn from_word_array(words: A) -> Self {
+        UnsignedInteger { words }
+    }
+    /// Create an integer from an iterator of words,
+    /// least significant first
+    ///
+    /// Any trailing zero words are dropped, so the words
+    /// don't need to be normalized beforehand.
+    /// They never take up any space, so they don't count against a fixed capacity.
+    ///
+    /// Errors if allocating space fails
+    pub fn from_words_iter<I: IntoIterator<Item = Word>>(iter: I) -> Result<Self, A::AllocErr> {
+        let mut res = Self::ZERO;
+        // Zeros are only pushed once a nonzero word follows them
+        let mut pending_zeros = 0;
+        for word in iter {
+            if word.0 == 0 {
+                pending_zeros += 1;
+                continue;
+            }
+            res.words.reserve(pending_zeros + 1)?;
+            for _ in 0..pending_zeros {
+                unsafe { res.words.unchecked_push(Word(0)) };
+            }
+            unsafe { res.words.unchecked_push(word) };
+            pending_zeros = 0;
+        }
+        debug_assert_eq!(res.check_invariants(), Ok(()));
+        Ok(res)
+    }
+    /// Create an integer from an array of words,
+    /// checking that it is normalized
+    ///
+    /// Errors if the most significant word is zero.
+    #[inline]
+    pub fn try_from_word_array(words: A) -> Result<Self, NotNormalized> {
+        match words.as_ref().last() {
+            Some(Word(0)) => Err(NotNormalized { len: words.len() }),
+            _ => Ok(UnsignedInteger { words })
+        }
+    }
+    /// Drop any trailing zero words, restoring the invariant
+    /// that the most significant word is nonzero
+    ///
+    /// This fixes up integers created by [UnsignedInteger::from_word_array]
+    /// with padding zero words. Every other operation already keeps integers normalized.
+    #[inline]
+    pub fn normalize(&mut self) {
+        self.trim();
+    }
+    /// Drop any trailing zero words left behind by an operation
+    #[inline]
+    pub(crate) fn trim(&mut self) {
+        let len = normalized_len(self.words());
+        self.words.truncate(len);
+    }
+    /// Check that this integer satisfies all of its invariants
+    ///
+    /// The most significant word must be nonzero,
+    /// so that zero is always represented by an empty array.
+    /// This also sanity checks the lengths reported by the [WordArray].
+    ///
+    /// This is useful for validating integers built by hand
+    /// (for example over FFI, or with a custom [WordArray]).
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let (len, capacity) = (self.words.len(), self.words.capacity());
+        let slice_len = self.words().len();
+        if len != slice_len {
+            return Err(InvariantViolation::SliceLengthMismatch { len, slice_len });
+        }
+        if len > capacity {
+            return Err(InvariantViolation::LengthExceedsCapacity { len, capacity });
+        }
+        match self.words().last() {
+            Some(Word(0)) => Err(InvariantViolation::TrailingZeroWord { index: len - 1 }),
+            _ => Ok(())
+        }
+    }
+    /// Clone this integer, reserving room for `extra_words`
+    /// more words than it currently needs
+    ///
+    /// This avoids reallocating in subsequent in-place operations,
+    /// when the maximum size of the result is known up front.
+    ///
+    /// Errors if allocating space fails
+    pub fn clone_with_capacity(&self, extra_words: usize) -> Result<Self, A::AllocErr> {
+        let len = self.words.len();
+        let capacity = len.checked_add(extra_words)
+            .ok_or_else(A::AllocErr::capacity_arithmetic_overflow)?;
+        let mut res = self.with_capacity_like(capacity)?;
+        for &word in self.words() {
+            unsafe { res.words.unchecked_push(word) };
+        }
+        Ok(res)
+    }
+    /// Create an integer equal to zero with the specified capacity,
+    /// allocating like this integer
+    ///
+    /// See [WordArray::with_capacity_like]
+    #[inline]
+    pub(crate) fn with_capacity_like(&self, capacity: usize) -> Result<Self, A::AllocErr> {
+        Ok(UnsignedInteger { words: self.words.with_capacity_like(capacity)? })
+    }
+    /// Copy this integer into a different type of [WordArray]
+    ///
+    /// For example, this can move a value parsed into a `Vec`
+    /// into a fixed-capacity [InlineWordArray](crate::memory::InlineWordArray).
+    ///
+    /// Errors if allocating space in the new array fails,
+    /// including if a fixed-capacity array is too small.
+    pub fn convert<B: WordArray>(&self) -> Result<UnsignedInteger<B>, B::AllocErr> {
+        let mut words = B::with_capacity(self.words.len())?;
+        for &word in self.words() {
+            unsafe { words.unchecked_push(word) };
+        }
+        // The words are already normalized
+        Ok(UnsignedInteger { words })
+    }
+    /// Parse a string in the specified radix,
+    /// allowing underscores to separate digits
+    ///
+    /// For example, `1_234_567` in base 10 or `dead_beef` in base 16.
+    /// Leading, trailing, or consecutive underscores are invalid digits.
+    ///
+    /// ## Panics
+    /// If the radix is not in `2..=36`
+    #[inline]
+    pub fn from_str_radix_with_separators(s: &str, radix: u32) -> Result<Self, ParseIntError<A::AllocErr>> {
+        crate::string::parse_unsigned_radix_with_separators(s, radix)
+    }
+    /// Replace this integer with the value of a string in the specified radix,
+    /// reusing its array
+    ///
+    /// This parses the same way as [Num::from_str_radix],
+    /// ignoring surrounding ASCII whitespace.
+    /// Since the result grows the existing array, this works for arrays
+    /// that can't be created from nothing, like [BumpWordArray](crate::memory::BumpWordArray).
+    ///
+    /// On error, this integer is left as zero.
+    ///
+    /// ## Panics
+    /// If the radix is not in `2..=36`
+    #[inline]
+    pub fn set_from_str_radix(&mut self, s: &str, radix: u32) -> Result<(), ParseIntError<A::AllocErr>> {
+        crate::string::parse_unsigned_radix_into(self, s.trim_matches(|c: char| c.is_ascii_whitespace()), radix)
+    }
+    /// Parse a hexadecimal string, without any `0x` prefix
+    ///
+    /// This gives the same result as parsing in radix 16,
+    /// but packs the digits directly into words instead of multiplying,
+    /// which is much faster for long strings like cryptographic keys.
+    #[inline]
+    pub fn from_hex_str(s: &str) -> Result<Self, ParseIntError<A::AllocErr>> {
+        crate::string::parse_unsigned_hex(s)
+    }
+    /// Parse a byte slice of ASCII digits in the specified radix
+    ///
+    /// This avoids converting to a `&str` when reading numbers
+    /// out of a larger buffer. Non-ASCII bytes are invalid digits,
+    /// reported as the replacement character `U+FFFD`.
+    ///
+    /// ## Panics
+    /// If the radix is not in `2..=36`
+    #[inline]
+    pub fn parse_unsigned_radix_bytes(bytes: &[u8], radix: u32) -> Result<Self, ParseIntError<A::AllocErr>> {
+        crate::string::parse_unsigned_radix_bytes(bytes, radix)
+    }
+    /// Parse a decimal string, allocating exactly as many words as the value needs
+    ///
+    /// The general parser reserves a lower bound on the length up front
+    /// and grows from there, which can leave slack capacity behind.
+    /// This instead derives a much tighter bound from the number of digits,
+    /// which is almost always exact. In the rare case it isn't,
+    /// the value moves into a new array exactly one word longer.
+    ///
+    /// Like [Num::from_str_radix], an optional leading `+` is allowed.
+    #[inline]
+    pub fn from_decimal_exact(s: &str) -> Result<Self, ParseIntError<A::AllocErr>> {
+        crate::string::parse_decimal_exact(s)
+    }
+    /// Create an integer from raw digit values in the specified radix,
+    /// most significant first
+    ///
+    /// Unlike parsing a string, each digit is the value itself
+    /// (so base-256 digits are just bytes).
+    /// Errors if a digit is not less than the radix,
+    /// or if allocating space fails.
+    ///
+    /// ## Panics
+    /// If the radix is not in `2..=256`
+    #[inline]
+    pub fn from_digits(digits: &[u8], radix: u32) -> Result<Self, ParseIntError<A::AllocErr>> {
+        crate::string::unsigned_from_digits(digits, radix)
+    }
+    /// Format this integer as a string in the specified radix
+    ///
+    /// Digits past nine are lowercase letters.
+    ///
+    /// ## Panics
+    /// If the radix is not in `2..=36`
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        crate::string::format_unsigned_radix(self, radix)
+    }
+    /// Format this integer as a string in the specified radix,
+    /// formatting independent parts of huge integers in parallel
+    ///
+    /// Formatting splits large integers in two with a division,
+    /// after which each half can be formatted separately.
+    /// Once the halves are big enough to be worth it,
+    /// this formats them on rayon's thread pool.
+    /// The output is always identical to [UnsignedInteger::to_str_radix].
+    ///
+    /// ## Panics
+    /// If the radix is not in `2..=36`
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub fn to_str_radix_parallel(&self, radix: u32) -> String {
+        crate::string::format_unsigned_radix_parallel(self, radix)
+    }
+    /// The number of decimal digits in this integer,
+    /// without needing to format it
+    ///
+    /// This is exactly the length of [UnsignedInteger::to_str_radix] in base 10,
+    /// so zero has a single digit.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn decimal_len(&self) -> u64 {
+        crate::string::decimal_len(self)
+    }
+    /// Format this integer approximately in scientific notation,
+    /// with the specified number of significant digits (like `1.235E8`)
+    ///
+    /// This is handy for logging enormous numbers compactly,
+    /// but it is lossy and can't be parsed back into the same integer.
+    /// The mantissa is rounded half up, padding with zeros if there are too few digits,
+    /// and at least one digit is always given.
+    /// The exponent is always exact, even when rounding carries into a new digit
+    /// (so `9995` with three digits is `1.00E4`).
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn to_scientific(&self, sig_digits: usize) -> String {
+        crate::string::format_scientific(self, sig_digits)
+    }
+    /// Create an integer from owned big-endian bytes
+    ///
+    /// Leading zero bytes are ignored.
+    ///
+    /// Reusing the buffer in place is never possible,
+    /// because the allocation of a [WordArray] is abstract,
+    /// and even a `Vec<Word>` can't take over memory allocated
+    /// with the alignment of a byte. Instead, the words are
+    /// packed directly into a single exactly-sized allocation,
+    /// without any intermediate copies.
+    ///
+    /// Errors if allocating space fails
+    #[cfg(feature = "alloc")]
+    pub fn from_bytes_be_owned(bytes: Vec<u8>) -> Result<Self, A::AllocErr> {
+        Self::from_bytes(&bytes, Endian::Big)
+    }
+    /// Create an integer from bytes in the specified order
+    ///
+    /// Leading zero bytes (in the order of significance) are ignored,
+    /// so an empty slice gives zero.
+    ///
+    /// Errors if allocating space fails
+    pub fn from_bytes(bytes: &[u8], endian: Endian) -> Result<Self, A::AllocErr> {
+        let significant = match endian {
+            Endian::Big => {
+                let start = bytes.iter().position(|&byte| byte != 0).unwrap_or(bytes.len());
+                &bytes[start..]
+            },
+            Endian::Little => {
+                let end = bytes.iter().rposition(|&byte| byte != 0).map_or(0, |index| index + 1);
+                &bytes[..end]
+            }
+        };
+        let mut words = A::with_capacity(significant.len().divide_round_up(8))?;
+        match endian {
+            Endian::Big => {
+                for chunk in significant.rchunks(8) {
+                    let word = chunk.iter().fold(0u64, |word, &byte| (word << 8) | byte as u64);
+                    unsafe { words.unchecked_push(Word(word)) };
+                }
+            },
+            Endian::Little => {
+                for chunk in significant.chunks(8) {
+                    let word = chunk.iter().rev().fold(0u64, |word, &byte| (word << 8) | byte as u64);
+                    unsafe { words.unchecked_push(Word(word)) };
+                }
+            }
+        }
+        Ok(UnsignedInteger { words })
+    }
+    /// Create an integer from little-endian bytes
+    ///
+    /// See [UnsignedInteger::from_bytes]
+    #[inline]
+    pub fn from_le_bytes(bytes: &[u8]) -> Result<Self, A::AllocErr> {
+        Self::from_bytes(bytes, Endian::Little)
+    }
+    /// Create an integer from big-endian bytes
+    ///
+    /// See [UnsignedInteger::from_bytes]
+    #[inline]
+    pub fn from_be_bytes(bytes: &[u8]) -> Result<Self, A::AllocErr> {
+        Self::from_bytes(bytes, Endian::Big)
+    }
+    /// Convert this integer into bytes in the specified order,
+    /// without any leading zero bytes
+    ///
+    /// Zero gives an empty vector.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self, endian: Endian) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.words().len() * 8);
+        for word in self.words() {
+            bytes.extend_from_slice(&word.0.to_le_bytes());
+        }
+        let len = bytes.iter().rposition(|&byte| byte != 0).map_or(0, |index| index + 1);
+        bytes.truncate(len);
+        if endian == Endian::Big {
+            bytes.reverse();
+        }
+        bytes
+    }
+    /// Convert this integer into little-endian bytes
+    ///
+    /// See [UnsignedInteger::to_bytes]
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_bytes(Endian::Little)
+    }
+    /// Convert this integer into big-endian bytes
+    ///
+    /// See [UnsignedInteger::to_bytes]
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        self.to_bytes(Endian::Big)
+    }
+    /// Create an integer from 64-bit limbs in the specified order
+    ///
+    /// Only the order of the limbs is affected,
+    /// since each limb is already a native integer.
+    /// Leading zero limbs (in the order of significance) are ignored,
+    /// so an empty slice gives zero.
+    ///
+    /// Errors if allocating space fails
+    pub fn import_limbs(limbs: &[u64], order: Endian) -> Result<Self, A::AllocErr> {
+        // Skip the leading zeros up front, so they never need any capacity
+        let significant = match order {
+            Endian::Big => &limbs[limbs.iter().take_while(|&&limb| limb == 0).count()..],
+            Endian::Little => &limbs[..limbs.iter().rposition(|&limb| limb != 0).map_or(0, |index| index + 1)]
+        };
+        match order {
+            Endian::Big => Self::from_words_iter(significant.iter().rev().map(|&limb| Word(limb))),
+            Endian::Little => Self::from_words_iter(significant.iter().map(|&limb| Word(limb)))
+        }
+    }
+    /// Create an integer from little-endian limbs
+    ///
+    /// See [UnsignedInteger::import_limbs]
+    #[inline]
+    pub fn import_limbs_le(limbs: &[u64]) -> Result<Self, A::AllocErr> {
+        Self::import_limbs(limbs, Endian::Little)
+    }
+    /// Create an integer from big-endian limbs
+    ///
+    /// See [UnsignedInteger::import_limbs]
+    #[inline]
+    pub fn import_limbs_be(limbs: &[u64]) -> Result<Self, A::AllocErr> {
+        Self::import_limbs(limbs, Endian::Big)
+    }
+    /// Convert this integer into 64-bit limbs in the specified order,
+    /// without any leading zero limbs
+    ///
+    /// Zero gives an empty vector.
+    #[cfg(feature = "alloc")]
+    pub fn export_limbs(&self, order: Endian) -> Vec<u64> {
+        let mut limbs: Vec<u64> = self.words().iter().map(|word| word.0).collect();
+        if order == Endian::Big {
+            limbs.reverse();
+        }
+        limbs
+    }
+    /// Convert this integer into little-endian limbs
+    ///
+    /// See [UnsignedInteger::export_limbs]
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn export_limbs_le(&self) -> Vec<u64> {
+        self.export_limbs(Endian::Little)
+    }
+    /// Convert this integer into big-endian limbs
+    ///
+    /// See [UnsignedInteger::export_limbs]
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn export_limbs_be(&self) -> Vec<u64> {
+        self.export_limbs(Endian::Big)
+    }
+    /// Get the underlying array of words
+    #[inline]
+    pub fn as_word_array(&self) -> &A {
+        &self.words
+    }
+    /// Compare the magnitude of this integer with the specified integer,
+    /// which may be backed by a different type of [WordArray]
+    ///
+    /// Both integers must be normalized (no trailing zero words),
+    /// since a longer array of words is assumed to be larger.
+    /// Otherwise, the words are compared from most significant to least.
+    #[inline]
+    pub fn cmp_magnitude<B: WordArray>(&self, other: &UnsignedInteger<B>) -> Ordering {
+        let (words, other_words) = (self.words(), other.words());
+        words.len().cmp(&other_words.len()).then_with(|| {
+            words.iter().rev().cmp(other_words.iter().rev())
+        })
+    }
+    /// Check if this integer is equal to the specified word,
+    /// without needing to allocate a temporary integer
+    #[inline]
+    pub fn eq_u64(&self, val: u64) -> bool {
+        self.cmp_u64(val) == Ordering::Equal
+    }
+    /// Compare this integer to the specified word,
+    /// without needing to allocate a temporary integer
+    #[inline]
+    pub fn cmp_u64(&self, val: u64) -> Ordering {
+        match *self.words() {
+            [] => 0.cmp(&val),
+            [word] => word.0.cmp(&val),
+            // Since we're normalized, anything longer is too large for a single word
+            _ => Ordering::Greater
+        }
+    }
+    /// Subtract the modulus from this integer a single time,
+    /// if this integer is greater than or equal to it
+    ///
+    /// Returns whether the subtraction happened.
+    /// This is the cheap conditional subtraction used after a modular addition,
+    /// and is much faster than a full division.
+    ///
+    /// The result is only fully reduced if `self < 2 * modulus`,
+    /// which holds for the sum of two already reduced values.
+    ///
+    /// ## Panics
+    /// In debug builds, if the modulus is zero.
+    /// Nothing is reduced modulo zero, so release builds just subtract zero and return `true`.
+    pub fn reduce_once(&mut self, modulus: &Self) -> bool {
+        debug_assert!(!modulus.is_empty(), "Reducing modulo zero");
+        if self.cmp_magnitude(modulus) == Ordering::Less {
+            return false;
+        }
+        self.sub_lesser_words(modulus.words());
+        true
+    }
+    /// Subtract the specified integer from this one,
+    /// returning zero if it is larger
+    ///
+    /// This matches [u64::saturating_sub],
+    /// and never fails because of underflow.
+    ///
+    /// Errors if allocating space for the result fails
+    pub fn saturating_sub(&self, other: &Self) -> Result<Self, A::AllocErr> {
+        if self.cmp_magnitude(other) != Ordering::Greater {
+            return self.with_capacity_like(0);
+        }
+        let mut res = self.clone_with_capacity(0)?;
+        res.sub_lesser_words(other.words());
+        Ok(res)
+    }
+    /// Subtract the specified little-endian words from this integer,
+    /// which must be known to be no larger than this integer
+    fn sub_lesser_words(&mut self, other: &[Word]) {
+        /*
+         * Grade school subtraction, borrowing from the next word
+         * whenever a word of `other` is larger. Afterwards,
+         * the high words may have cancelled out to zero,
+         * so drop them to keep the integer normalized.
+         */
+        let borrow = sub_words_assign(self.words.as_mut(), other);
+        debug_assert!(!borrow, "Subtraction underflowed");
+        self.trim();
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+    }
+    /// Convert this integer into width-`window` non-adjacent form (wNAF)
+    ///
+    /// Returns signed digits, least significant first,
+    /// such that `self == sum(digits[i] * 2**i)`.
+    /// Every nonzero digit is odd and less than `2**(window - 1)` in magnitude,
+    /// and at most one of any `window` consecutive digits is nonzero.
+    /// This minimizes the number of nonzero digits,
+    /// and is the standard input to windowed scalar multiplication.
+    ///
+    /// ## Panics
+    /// If the window is not in `2..=8`
+    #[cfg(feature = "alloc")]
+    pub fn to_wnaf(&self, window: u32) -> Vec<i8> {
+        assert!((2..=8).contains(&window), "Invalid window: {}", window);
+        let modulus = 1i64 << window;
+        let half = modulus >> 1;
+        let mut remaining: Vec<u64> = self.words().iter().map(|word| word.0).collect();
+        let mut digits = Vec::with_capacity(remaining.len() * 64 + 1);
+        loop {
+            while remaining.last() == Some(&0) {
+                remaining.pop();
+            }
+            if remaining.is_empty() {
+                break;
+            }
+            let mut digit = 0i64;
+            if remaining[0] & 1 == 1 {
+                // The signed residue of `remaining` modulo `2**window`
+                digit = (remaining[0] & (modulus as u64 - 1)) as i64;
+                if digit >= half {
+                    digit -= modulus;
+                }
+                /*
+                 * Subtract the digit, clearing the low `window` bits.
+                 * A positive digit equals those bits, so it never borrows.
+                 * A negative digit is added back, which may carry.
+                 */
+                if digit > 0 {
+                    remaining[0] -= digit as u64;
+                } else {
+                    let mut carry = (-digit) as u64;
+                    for word in remaining.iter_mut() {
+                        let (res, overflowed) = word.overflowing_add(carry);
+                        *word = res;
+                        carry = overflowed as u64;
+                        if carry == 0 {
+                            break;
+                        }
+                    }
+                    if carry != 0 {
+                        remaining.push(carry);
+                    }
+                }
+            }
+            digits.push(digit as i8);
+            // Shift right by a single bit
+            for index in 0..remaining.len() {
+                let high = remaining.get(index + 1).copied().unwrap_or(0);
+                remaining[index] = (remaining[index] >> 1) | (high << 63);
+            }
+        }
+        digits
+    }
+    /// Shift this integer left by the specified number of bits
+    ///
+    /// Exactly the extra words needed by the result are reserved up front,
+    /// so a fixed-capacity array only fails if the result doesn't fit.
+    ///
+    /// Errors if allocating space for the extra words fails,
+    /// in which case this integer is left unchanged.
+    pub fn shl_assign(&mut self, bits: u32) -> Result<(), A::AllocErr> {
+        let len = self.words.len();
+        if len == 0 || bits == 0 {
+            return Ok(());
+        }
+        let word_shift = (bits / 64) as usize;
+        let bit_shift = bits % 64;
+        // Whether the bits shifted out of the top word need an extra word
+        let top_carries = bit_shift != 0
+            && self.words()[len - 1].0.leading_zeros() < bit_shift;
+        let extra_words = word_shift + top_carries as usize;
+        self.words.reserve(extra_words)?;
+        for _ in 0..extra_words {
+            unsafe { self.words.unchecked_push(Word(0)) };
+        }
+        /*
+         * Move each word up by `word_shift` words, carrying the high bits
+         * of the word below it. Going from most significant to least means
+         * we never overwrite a word before it has been moved.
+         */
+        let words = self.words.as_mut();
+        if top_carries {
+            words[len + word_shift] = Word(words[len - 1].0 >> (64 - bit_shift));
+        }
+        for index in (0..len).rev() {
+            let mut word = words[index].0 << bit_shift;
+            if bit_shift != 0 && index > 0 {
+                word |= words[index - 1].0 >> (64 - bit_shift);
+            }
+            words[index + word_shift] = Word(word);
+        }
+        for word in &mut words[..word_shift] {
+            *word = Word(0);
+        }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+        Ok(())
+    }
+    /// Shift this integer right by the specified number of bits
+    ///
+    /// Shifting by at least the bit length of this integer gives zero.
+    /// This never needs to allocate.
+    pub fn shr_assign(&mut self, bits: u32) {
+        let len = self.words.len();
+        let word_shift = (bits / 64) as usize;
+        let bit_shift = bits % 64;
+        if word_shift >= len {
+            self.words.clear();
+            return;
+        }
+        /*
+         * Move each word down by `word_shift` words, carrying the low bits
+         * of the word above it. Going from least significant to most means
+         * we never overwrite a word before it has been moved.
+         */
+        let new_len = len - word_shift;
+        let words = self.words.as_mut();
+        for index in 0..new_len {
+            let source = index + word_shift;
+            let mut word = words[source].0 >> bit_shift;
+            if bit_shift != 0 && source + 1 < len {
+                word |= words[source + 1].0 << (64 - bit_shift);
+            }
+            words[index] = Word(word);
+        }
+        // Only the top word can have been shifted down to zero
+        self.words.truncate(new_len);
+        self.trim();
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+    }
+    /// Flip the low `bits` bits of this integer,
+    /// returning the result as a new integer
+    ///
+    /// Since integers have no fixed width, there is no global bitwise NOT.
+    /// Instead, this integer is treated as having the specified width,
+    /// so any higher bits are discarded and the result is less than `2**bits`.
+    ///
+    /// Errors if allocating space fails
+    pub fn complement_to_bits(&self, bits: u64) -> Result<Self, A::AllocErr> {
+        let len = usize::try_from(bits.divide_round_up(Word::BITS))
+            .map_err(|_| A::AllocErr::capacity_arithmetic_overflow())?;
+        let mut words = self.words.with_capacity_like(len)?;
+        for index in 0..len {
+            let word = self.words().get(index).map_or(0, |word| word.0);
+            unsafe { words.unchecked_push(Word(!word)) };
+        }
+        let top_bits = bits % Word::BITS;
+        if top_bits != 0 {
+            let top_word = &mut words.as_mut()[len - 1];
+            top_word.0 &= (1 << top_bits) - 1;
+        }
+        let mut res = UnsignedInteger { words };
+        res.trim();
+        Ok(res)
+    }
+    /// Combine the words of this integer with the words of the specified integer,
+    /// treating any missing words of the shorter one as zero
+    ///
+    /// Errors if allocating space fails
+    fn bitwise_assign(&mut self, other: &Self, func: impl Fn(u64, u64) -> u64) -> Result<(), A::AllocErr> {
+        let (len, other_words) = (self.words.len(), other.words());
+        if other_words.len() > len {
+            self.words.reserve(other_words.len() - len)?;
+            for _ in len..other_words.len() {
+                unsafe { self.words.unchecked_push(Word(0)) };
+            }
+        }
+        for (index, word) in self.words.as_mut().iter_mut().enumerate() {
+            let other = other_words.get(index).map_or(0, |word| word.0);
+            word.0 = func(word.0, other);
+        }
+        self.trim();
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+        Ok(())
+    }
+    /// Attempt to add the specified integer to this integer
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn add(&mut self, other: &Self) -> Result<(), A::AllocErr> {
+        self.add_heterogeneous(other)
+    }
+    /// Add the specified integer to this integer,
+    /// returning the sum as a new integer
+    ///
+    /// Unlike [UnsignedInteger::add], neither operand is modified.
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn checked_add(&self, other: &Self) -> Result<Self, A::AllocErr> {
+        let mut res = self.with_capacity_like(0)?;
+        self.add_into(other, &mut res)?;
+        Ok(res)
+    }
+    /// Attempt to add an integer backed by a different
+    /// type of [WordArray] to this integer
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn add_heterogeneous<B: WordArray>(&mut self, other: &UnsignedInteger<B>) -> Result<(), A::AllocErr> {
+        let other_words = other.words();
+        let required = sum_len(self.words(), other_words, self.words.capacity());
+        self.words.reserve(required - self.words.len())?;
+        unsafe { self.unchecked_add_words_carry_chain(other_words) };
+        Ok(())
+    }
+    /// Add the specified integer to this integer,
+    /// wrapping around to fit in the specified number of bits
+    ///
+    /// This simulates fixed-width addition, like a hardware register.
+    /// Only the low `bits` bits of the sum are kept.
+    ///
+    /// Errors if allocating space fails
+    pub fn wrapping_add_bits(&mut self, other: &Self, bits: u64) -> Result<(), A::AllocErr> {
+        self.add(other)?;
+        self.truncate_to_bits(bits);
+        Ok(())
+    }
+    /// Add the specified integer to this integer,
+    /// wrapping around to fit in the specified number of bits,
+    /// and returning whether the sum overflowed
+    ///
+    /// This mirrors [u64::overflowing_add] for arbitrary widths,
+    /// where overflow means the sum needed more than `bits` bits.
+    ///
+    /// Errors if allocating space fails
+    pub fn overflowing_add_bits(&mut self, other: &Self, bits: u64) -> Result<bool, A::AllocErr> {
+        self.add(other)?;
+        let overflowed = self.bit_len() > bits;
+        if overflowed {
+            self.truncate_to_bits(bits);
+        }
+        Ok(overflowed)
+    }
+    /// Add the specified integer to this integer,
+    /// saturating at the maximum value of the specified number of bits
+    ///
+    /// If the sum doesn't fit in `bits` bits, the result is `2**bits - 1`.
+    ///
+    /// Errors if allocating space fails
+    pub fn saturating_add_bits(&mut self, other: &Self, bits: u64) -> Result<(), A::AllocErr> {
+        self.add(other)?;
+        if self.bit_len() > bits {
+            *self = self.with_capacity_like(0)?.complement_to_bits(bits)?;
+        }
+        Ok(())
+    }
+    /// Clear all the bits at or above the specified index,
+    /// keeping only the low `bits` bits
+    ///
+    /// This is equivalent to reducing modulo `2**bits`, and never allocates.
+    pub fn truncate_to_bits(&mut self, bits: u64) {
+        if bits >= self.bit_len() {
+            return;
+        }
+        // Since `bits` is less than the bit length, this index is in bounds
+        let word_index = (bits / Word::BITS) as usize;
+        let top_bits = bits % Word::BITS;
+        if top_bits == 0 {
+            self.words.truncate(word_index);
+        } else {
+            self.words.truncate(word_index + 1);
+            self.words_mut()[word_index].0 &= (1 << top_bits) - 1;
+        }
+        self.trim();
+    }
+    /// Add the specified integer to this integer,
+    /// writing the sum into `out`
+    ///
+    /// Any existing value in `out` is discarded, but its allocation
+    /// is reused, only growing if it can't hold the sum.
+    /// This avoids allocating in hot loops, by reusing the same buffer.
+    ///
+    /// Errors if allocating space fails
+    pub fn add_into(&self, other: &Self, out: &mut Self) -> Result<(), A::AllocErr> {
+        out.words.clear();
+        out.words.reserve(sum_len(self.words(), other.words(), out.words.capacity()))?;
+        for &word in self.words() {
+            unsafe { out.words.unchecked_push(word) };
+        }
+        // We just reserved room for any carry word
+        unsafe { out.unchecked_add_words_carry_chain(other.words()) };
+        Ok(())
+    }
+    /// Add the specified integer to this integer,
+    /// without checking for overflow
+    ///
+    /// ## Safety
+    /// Assumes `self.words.capacity >= max(self.len, other.len) + 1`
+    #[inline]
+    pub unsafe fn unchecked_add(&mut self, other: &Self) {
+        self.unchecked_add_words(other.words())
+    }
+    /// Add the specified little-endian words to this integer,
+    /// without checking for overflow
+    ///
+    /// ## Safety
+    /// Assumes `self.words.capacity >= max(self.len, other.len) + 1`
+    unsafe fn unchecked_add_words(&mut self, other: &[Word]) {
+        /*
+         * Grade school addition algorithm:
+         * For example,
+         * 1 2 3
+         *   4 5
+         * ------
+         * 1 6 7
+         *
+         * Just iterate over each corresponding index,
+         * propagating carries as needed.
+         */
+        let mut carry = false;
+        #[inline]
+        unsafe fn ensure_iter<A: WordArray>(words: &mut A, target_index: usize) -> &mut Word {
+            if target_index >= words.len() {
+                /*
+                 * Worst case, we should only need to append a single word
+                 * to the array. That is because we also did the necessary
+                 * bounds check last index as well. For example, if at the start of the call,
+                 * `self.len() == 2, other.len() == 4`, when we are at iteration #4 of 'other',
+                 * we can rely on the fact that iteration #3 would've already expanded `self.words` up
+                 * to length three (so we would only need to add one more word, not two).
+                 */
+                debug_assert_eq!(
+                    target_index,
+                    words.len()
+                );
+                unsafe {
+                    words.unchecked_push(Word(0));
+                }
+            }
+            debug_assert!(target_index < words.len());
+            words.as_mut().get_unchecked_mut(target_index)
+        }
+        for (addend_index, addend) in other.iter().enumerate() {
+            let target_word = unsafe { ensure_iter(&mut self.words, addend_index) };
+            let (addend, new_carry) = addend.0.overflowing_add(carry as u64);
+            carry = new_carry;
+            let (res, new_carry) = target_word.0.overflowing_add(addend);
+            // If the first addition carried, the addend is zero
+            debug_assert!(!(carry && new_carry), "Double carry");
+            carry |= new_carry;
+            target_word.0 = res;
+        }
+        // Propagate the final carry through any remaining words
+        let mut target_index = other.len();
+        while carry {
+            let target_word = unsafe { ensure_iter(&mut self.words, target_index) };
+            let (res, new_carry) = target_word.0.overflowing_add(1);
+            target_word.0 = res;
+            carry = new_carry;
+            target_index += 1;
+        }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+    }
+    /// Add the specified integer to this integer,
+    /// without checking for overflow
+    ///
+    /// This gives the same result as [UnsignedInteger::unchecked_add],
+    /// but accumulates each word in a [u128] so the carry is a plain word.
+    /// That avoids branching on carries in the main loop.
+    ///
+    /// ## Safety
+    /// Assumes `self.words.capacity >= max(self.len, other.len) + 1`
+    #[inline]
+    pub unsafe fn unchecked_add_carry_chain(&mut self, other: &Self) {
+        self.unchecked_add_words_carry_chain(other.words())
+    }
+    /// Add the specified little-endian words to this integer,
+    /// accumulating each word in a [u128]
+    ///
+    /// Past a handful of words, this is about twice as fast as
+    /// [UnsignedInteger::unchecked_add_words] (see `benches/addition.rs`),
+    /// so the fallible additions use it.
+    ///
+    /// ## Safety
+    /// Assumes the capacity can hold the sum, including any carry word.
+    /// A capacity of `max(self.len, other.len) + 1` is always sufficient.
+    unsafe fn unchecked_add_words_carry_chain(&mut self, other: &[Word]) {
+        debug_assert!(self.words.capacity() >= sum_len(self.words(), other, 0));
+        // Extend with zeros up front, so the loop doesn't need to check
+        while self.words.len() < other.len() {
+            unsafe { self.words.unchecked_push(Word(0)) };
+        }
+        let (low, high) = self.words.as_mut().split_at_mut(other.len());
+        let mut carry = 0u64;
+        for (target_word, addend) in low.iter_mut().zip(other) {
+            // The sum is at most `2 * (2**64 - 1) + 1`, so the carry is zero or one
+            let sum = target_word.0 as u128 + addend.0 as u128 + carry as u128;
+            target_word.0 = sum as u64;
+            carry = (sum >> 64) as u64;
+        }
+        // Propagate the final carry through any remaining words
+        for target_word in high {
+            if carry == 0 {
+                break;
+            }
+            let sum = target_word.0 as u128 + carry as u128;
+            target_word.0 = sum as u64;
+            carry = (sum >> 64) as u64;
+        }
+        if carry != 0 {
+            unsafe { self.words.unchecked_push(Word(carry)) };
+        }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+    }
+    /// Attempt to subtract the specified integer from this integer
+    ///
+    /// The other integer may use a different kind of [WordArray],
+    /// like subtracting an inline integer from a heap allocated one.
+    ///
+    /// Errors with [SubError::Underflow] if the other integer is larger,
+    /// in which case this integer is left unchanged.
+    #[inline]
+    pub fn sub<B: WordArray>(&mut self, other: &UnsignedInteger<B>) -> Result<(), SubError<A::AllocErr>> {
+        if self.cmp_magnitude(other) == Ordering::Less {
+            return Err(SubError::Underflow);
+        }
+        self.sub_lesser_words(other.words());
+        Ok(())
+    }
+    /// Subtract the specified integer from this integer,
+    /// without checking for underflow
+    ///
+    /// Assumes `self >= other`. Subtraction never needs to allocate,
+    /// so violating this can't cause undefined behavior,
+    /// but the result is meaningless (and panics with debug assertions).
+    #[inline]
+    pub fn unchecked_sub(&mut self, other: &Self) {
+        self.sub_lesser_words(other.words());
+    }
+    /// Subtract the specified integer from this integer,
+    /// returning the difference as a new integer
+    ///
+    /// The other integer may use a different kind of [WordArray].
+    ///
+    /// Returns `None` if the other integer is larger.
+    ///
+    /// Errors if allocating space for the result fails
+    pub fn checked_sub<B: WordArray>(&self, other: &UnsignedInteger<B>) -> Result<Option<Self>, A::AllocErr> {
+        if self.cmp_magnitude(other) == Ordering::Less {
+            return Ok(None);
+        }
+        let mut res = self.clone_with_capacity(0)?;
+        res.sub_lesser_words(other.words());
+        Ok(Some(res))
+    }
+    /// Raise this integer to the specified power
+    ///
+    /// Zero to the power of zero is one, by convention.
+    ///
+    /// The result has about `exp` times as many bits as this integer,
+    /// so memory usage grows linearly with the exponent.
+    /// Use [UnsignedInteger::pow_capped] to guard against huge results,
+    /// or [UnsignedInteger::pow_mod] to keep them reduced.
+    ///
+    /// Errors if allocating space fails
+    pub fn pow(&self, exp: u32) -> Result<Self, A::AllocErr> {
+        if exp == 0 {
+            let mut res = self.with_capacity_like(1)?;
+            res.set_one()?;
+            return Ok(res);
+        }
+        // Left-to-right binary exponentiation, starting from the top bit
+        let mut res = self.clone_with_capacity(0)?;
+        let mut scratch = self.with_capacity_like(0)?;
+        for shift in (0..(31 - exp.leading_zeros())).rev() {
+            res.mul_into(&res, &mut scratch)?;
+            core::mem::swap(&mut res, &mut scratch);
+            if (exp >> shift) & 1 == 1 {
+                res.mul_into(self, &mut scratch)?;
+                core::mem::swap(&mut res, &mut scratch);
+            }
+        }
+        Ok(res)
+    }
+    /// Raise this integer to the specified power,
+    /// unless the result would need more than `max_bits` bits
+    ///
+    /// Returns `None` if the result would be too large.
+    /// A lower bound on the size of the result is checked up front,
+    /// and the intermediate results are checked while squaring,
+    /// so an oversized result is rejected before doing most of the work.
+    /// This protects against accidentally exhausting memory with large exponents.
+    ///
+    /// Errors if allocating space fails
+    pub fn pow_capped(&self, exp: u64, max_bits: u64) -> Result<Option<Self>, A::AllocErr> {
+        let fits = |res: &Self| bit_length(res.words()) <= max_bits;
+        let bits = bit_length(self.words());
+        if exp == 0 || bits <= 1 {
+            // The result is trivially zero or one
+            let mut res = self.with_capacity_like(0)?;
+            if exp == 0 || bits == 1 {
+                res.set_one()?;
+            }
+            return Ok(Some(res).filter(fits));
+        }
+        // Since `self >= 2**(bits - 1)`, the result has at least this many bits
+        match (bits - 1).checked_mul(exp).and_then(|min_bits| min_bits.checked_add(1)) {
+            Some(min_bits) if min_bits <= max_bits => {},
+            _ => return Ok(None)
+        }
+        /*
+         * Left-to-right binary exponentiation. Since `self >= 2`,
+         * every intermediate result is no larger than the final one,
+         * so we can give up as soon as any of them are too large.
+         */
+        let mut res = self.clone_with_capacity(0)?;
+        let mut scratch = self.with_capacity_like(0)?;
+        for shift in (0..(63 - exp.leading_zeros())).rev() {
+            res.mul_into(&res, &mut scratch)?;
+            core::mem::swap(&mut res, &mut scratch);
+            if (exp >> shift) & 1 == 1 {
+                res.mul_into(self, &mut scratch)?;
+                core::mem::swap(&mut res, &mut scratch);
+            }
+            if !fits(&res) {
+                return Ok(None);
+            }
+        }
+        Ok(Some(res))
+    }
+    /// Add the specified integer to this integer,
+    /// without ever growing the underlying array
+    ///
+    /// This is intended for fixed-capacity arrays,
+    /// where the result is known to fit in the existing capacity.
+    ///
+    /// Errors if the sum needs more words than the current capacity,
+    /// in which case this integer is left unchanged.
+    pub fn add_assign_nogrow(&mut self, other: &Self) -> Result<(), CapacityExceeded> {
+        let capacity = self.words.capacity();
+        let len = self.words.len().max(other.words.len());
+        if len > capacity {
+            return Err(CapacityExceeded { required: len });
+        }
+        if len == capacity && add_overflows(self.words(), other.words()) {
+            return Err(CapacityExceeded { required: len + 1 });
+        }
+        // We just checked there is room for any words this needs to push
+        unsafe { self.unchecked_add_words_carry_chain(other.words()) };
+        Ok(())
+    }
+    /// Attempt to multiply this integer by the specified integer,
+    /// returning the product as a new integer
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn mul(&self, other: &Self) -> Result<Self, A::AllocErr> {
+        let mut res = self.with_capacity_like(0)?;
+        self.mul_into(other, &mut res)?;
+        Ok(res)
+    }
+    /// Multiply this integer by the specified integer,
+    /// writing the product into `out`
+    ///
+    /// Any existing value in `out` is discarded, but its allocation
+    /// is reused, only growing if it can't hold the combined words of both operands.
+    /// Because `out` is borrowed mutably, it can never alias either operand.
+    ///
+    /// Errors if allocating space fails, including if the
+    /// length of the product would overflow a [usize]
+    #[inline]
+    pub fn mul_into(&self, other: &Self, out: &mut Self) -> Result<(), A::AllocErr> {
+        self.mul_into_with(other, out, &MulConfig::DEFAULT)
+    }
+    /// Multiply this integer by the specified integer,
+    /// using the thresholds in the specified configuration
+    ///
+    /// This gives the same result as [UnsignedInteger::mul],
+    /// only switching algorithms at different lengths.
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn mul_with(&self, other: &Self, config: &MulConfig) -> Result<Self, A::AllocErr> {
+        let mut res = self.with_capacity_like(0)?;
+        self.mul_into_with(other, &mut res, config)?;
+        Ok(res)
+    }
+    /// Multiply this integer by the specified integer, writing the product into `out`,
+    /// using the thresholds in the specified configuration
+    ///
+    /// This is the same as [UnsignedInteger::mul_into],
+    /// only switching algorithms at different lengths.
+    ///
+    /// Errors if allocating space fails, including if the
+    /// length of the product would overflow a [usize]
+    pub fn mul_into_with(&self, other: &Self, out: &mut Self, config: &MulConfig) -> Result<(), A::AllocErr> {
+        out.words.clear();
+        if self.is_empty() || other.is_empty() {
+            return Ok(());
+        }
+        // Check the length before touching the words, so an overflow never reserves anything
+        let product_len = self.len().checked_add(other.len())
+            .ok_or_else(A::AllocErr::capacity_arithmetic_overflow)?;
+        /*
+         * Multiplying by a power of two is just a shift.
+         * A single word is already one pass of grade school multiplication,
+         * which benchmarks faster than shifting, so only longer powers take this path.
+         */
+        let power_exponent = |value: &Self| if value.len() > 1 { value.power_of_two_exponent() } else { None };
+        let shifted = match (power_exponent(self), power_exponent(other)) {
+            (_, Some(shift)) => Some((self, shift)),
+            (Some(shift), None) => Some((other, shift)),
+            (None, None) => None
+        };
+        if let Some((value, shift)) = shifted {
+            out.words.reserve(product_len)?;
+            for &word in value.words() {
+                unsafe { out.words.unchecked_push(word) };
+            }
+            // The product fits in `product_len` words, which we already reserved
+            return UnsignedInteger::shl_assign(out, shift);
+        }
+        out.words.reserve(product_len)?;
+        for _ in 0..product_len {
+            unsafe { out.words.unchecked_push(Word(0)) };
+        }
+        let target = out.words.as_mut();
+        mul_words(target, self.words(), other.words(), config);
+        /*
+         * The product of an n-word and m-word integer
+         * needs at least `n + m - 1` words, so only the top word can be zero.
+         */
+        if target[product_len - 1].0 == 0 {
+            out.words.truncate(product_len - 1);
+        }
+        Ok(())
+    }
+    /// Divide this integer by the specified integer,
+    /// returning both the quotient and the remainder
+    ///
+    /// Errors with [DivError::DivisionByZero] if the divisor is zero,
+    /// or if allocating space fails
+    pub fn div_rem(&self, divisor: &Self) -> Result<(Self, Self), DivError<A::AllocErr>> {
+        if let Some(res) = self.div_rem_short(divisor)? {
+            return Ok(res);
+        }
+        let mut ctx = DivContext::new();
+        let quotient = self.div_rem_knuth(divisor, &mut ctx)?;
+        // The context is discarded anyway, so take its remainder instead of copying
+        Ok((quotient, ctx.remainder))
+    }
+    /// Divide this integer by the specified integer,
+    /// using the scratch space in the specified context
+    ///
+    /// This gives the same result as [UnsignedInteger::div_rem],
+    /// but reuses the context's buffers instead of allocating new ones each time.
+    /// Only the quotient and remainder themselves are freshly allocated.
+    ///
+    /// Errors with [DivError::DivisionByZero] if the divisor is zero,
+    /// or if allocating space fails
+    pub fn div_rem_with(&self, divisor: &Self, ctx: &mut DivContext<A>) -> Result<(Self, Self), DivError<A::AllocErr>> {
+        if let Some(res) = self.div_rem_short(divisor)? {
+            return Ok(res);
+        }
+        let quotient = self.div_rem_knuth(divisor, ctx)?;
+        Ok((quotient, ctx.remainder.clone_with_capacity(0)?))
+    }
+    /// Handle the cases of division that don't need Algorithm D,
+    /// returning `None` if it's needed after all
+    fn div_rem_short(&self, divisor: &Self) -> Result<Option<(Self, Self)>, DivError<A::AllocErr>> {
+        let divisor_len = divisor.words().len();
+        if divisor_len == 0 {
+            return Err(DivError::DivisionByZero);
+        }
+        if self.cmp_magnitude(divisor) == Ordering::Less {
+            return Ok(Some((self.with_capacity_like(0)?, self.clone_with_capacity(0)?)));
+        }
+        if let Some(shift) = divisor.power_of_two_exponent() {
+            // Dividing by a power of two is just a shift
+            let mut quotient = self.clone_with_capacity(0)?;
+            UnsignedInteger::shr_assign(&mut quotient, shift);
+            let mut remainder = self.clone_with_capacity(0)?;
+            remainder.truncate_to_bits(shift as u64);
+            return Ok(Some((quotient, remainder)));
+        }
+        if divisor_len == 1 {
+            // Short division is much simpler
+            let mut quotient = self.clone_with_capacity(0)?;
+            let remainder = quotient.div_rem_u64(divisor.words()[0].0)?;
+            let mut res = self.with_capacity_like(0)?;
+            res.set(remainder)?;
+            return Ok(Some((quotient, res)));
+        }
+        if self.bit_len() - divisor.bit_len() < SMALL_QUOTIENT_BITS {
+            return Ok(Some(self.div_rem_greedy(divisor)?));
+        }
+        Ok(None)
+    }
+    /// Divide this integer by the specified integer,
+    /// assuming the quotient is small
+    ///
+    /// This subtracts shifted copies of the divisor greedily,
+    /// taking time proportional to the number of bits in the quotient.
+    /// That's much cheaper than the general algorithm when the operands
+    /// are close in magnitude, like reducing a value slightly larger than a modulus.
+    /// [UnsignedInteger::div_rem] already does this automatically
+    /// when the quotient has fewer than [SMALL_QUOTIENT_BITS] bits.
+    ///
+    /// The result is always correct, but it's slow if the quotient is large.
+    ///
+    /// Errors with [DivError::DivisionByZero] if the divisor is zero,
+    /// or if allocating space fails
+    pub fn div_rem_small_quotient(&self, divisor: &Self) -> Result<(Self, Self), DivError<A::AllocErr>> {
+        if divisor.words().is_empty() {
+            return Err(DivError::DivisionByZero);
+        }
+        if self.cmp_magnitude(divisor) == Ordering::Less {
+            return Ok((self.with_capacity_like(0)?, self.clone_with_capacity(0)?));
+        }
+        self.div_rem_greedy(divisor)
+    }
+    /// Divide by subtracting shifted copies of the divisor
+    ///
+    /// The divisor must be nonzero, and must not be greater than this integer.
+    fn div_rem_greedy(&self, divisor: &Self) -> Result<(Self, Self), DivError<A::AllocErr>> {
+        debug_assert!(self.cmp_magnitude(divisor) != Ordering::Less);
+        // The quotient is less than `2**(quotient_bits + 1)`
+        let quotient_bits = self.bit_len() - divisor.bit_len();
+        let mut remainder = self.clone_with_capacity(0)?;
+        // Shifting can't take the divisor past the length of this integer
+        let mut shifted = divisor.clone_with_capacity(self.words.len() - divisor.words.len())?;
+        let shift = u32::try_from(quotient_bits)
+            .map_err(|_| A::AllocErr::capacity_arithmetic_overflow())?;
+        UnsignedInteger::shl_assign(&mut shifted, shift)?;
+        let mut quotient = self.with_capacity_like((quotient_bits / Word::BITS) as usize + 1)?;
+        for bit in (0..=quotient_bits).rev() {
+            if remainder.cmp_magnitude(&shifted) != Ordering::Less {
+                let borrow = sub_words_assign(remainder.words.as_mut(), shifted.words());
+                debug_assert!(!borrow);
+                remainder.trim();
+                // Bits are set from the top down, so only the first one can allocate
+                quotient.set_bit(bit, true)?;
+            }
+            UnsignedInteger::shr_assign(&mut shifted, 1);
+        }
+        Ok((quotient, remainder))
+    }
+    /// Divide using Knuth's Algorithm D, returning the quotient
+    ///
+    /// The remainder is left in the context.
+    /// The divisor must have at least two words,
+    /// and must not be greater than this integer.
+    fn div_rem_knuth(&self, divisor: &Self, ctx: &mut DivContext<A>) -> Result<Self, DivError<A::AllocErr>> {
+        let mut quotient = self.with_capacity_like(0)?;
+        self.div_rem_knuth_into(divisor, ctx, &mut quotient)?;
+        Ok(quotient)
+    }
+    /// Divide using Knuth's Algorithm D, writing the quotient into `quotient`
+    ///
+    /// Like [UnsignedInteger::div_rem_knuth], the remainder is left in the context.
+    /// The quotient's allocation is reused, only growing if it's too small.
+    fn div_rem_knuth_into(&self, divisor: &Self, ctx: &mut DivContext<A>, quotient: &mut Self) -> Result<(), A::AllocErr> {
+        let divisor_len = divisor.words().len();
+        debug_assert!(divisor_len >= 2);
+        /*
+         * Knuth's Algorithm D (TAOCP Vol 2, 4.3.1)
+         *
+         * First normalize both operands so the top bit of the divisor is set,
+         * which guarantees each estimated quotient word is off by at most two.
+         * The dividend needs an extra word on top, even if the shift didn't add one.
+         * That word is kept in a local instead of the remainder's array,
+         * so a fixed-capacity array can divide values that fill it.
+         */
+        let shift = divisor.words()[divisor_len - 1].0.leading_zeros();
+        let DivContext { remainder, divisor: normalized_divisor } = ctx;
+        let mut overflow = remainder.copy_shifted_from(self, shift)?;
+        let divisor_overflow = normalized_divisor.copy_shifted_from(divisor, shift)?;
+        debug_assert_eq!(divisor_overflow, Word(0));
+        let quotient_len = remainder.words.len() + 1 - divisor_len;
+        quotient.words.clear();
+        if quotient.words.capacity() < quotient_len {
+            quotient.words = self.words.with_capacity_like(quotient_len)?;
+        }
+        for _ in 0..quotient_len {
+            unsafe { quotient.words.unchecked_push(Word(0)) };
+        }
+        let v = normalized_divisor.words();
+        let u = remainder.words.as_mut();
+        let q = quotient.words.as_mut();
+        let (v1, v2) = (v[divisor_len - 1].0 as u128, v[divisor_len - 2].0 as u128);
+        for j in (0..quotient_len).rev() {
+            // Only the first window reaches past the array, up to the overflow word
+            let (low, high) = u.split_at_mut(j + divisor_len);
+            let window = &mut low[j..];
+            let top_word = high.first_mut().unwrap_or(&mut overflow);
+            // Estimate the quotient word from the top two words of the remainder
+            let top = ((top_word.0 as u128) << 64) | (window[divisor_len - 1].0 as u128);
+            let mut qhat = top / v1;
+            let mut rhat = top % v1;
+            while qhat > u64::MAX as u128
+                || qhat * v2 > ((rhat << 64) | (window[divisor_len - 2].0 as u128)) {
+                qhat -= 1;
+                rhat += v1;
+                if rhat > u64::MAX as u128 {
+                    break;
+                }
+            }
+            // Subtract `qhat * v`, adding back once in the rare case it was still too large
+            if mul_sub_word_assign(window, top_word, v, Word(qhat as u64)) {
+                qhat -= 1;
+                let mut carry = false;
+                for (target_word, addend) in window.iter_mut().zip(v) {
+                    let (res, first_carry) = target_word.0.overflowing_add(addend.0);
+                    let (res, second_carry) = res.overflowing_add(carry as u64);
+                    target_word.0 = res;
+                    carry = first_carry | second_carry;
+                }
+                // This cancels out the borrow from the subtraction
+                top_word.0 = top_word.0.wrapping_add(carry as u64);
+            }
+            q[j] = Word(qhat as u64);
+        }
+        // The remainder is less than the divisor, so the overflow word was used up
+        debug_assert_eq!(overflow, Word(0));
+        quotient.trim();
+        // Undo the normalization to get the actual remainder
+        remainder.trim();
+        remainder.shr_assign(shift);
+        Ok(())
+    }
+    /// Replace this integer with the low words of `value << shift`,
+    /// reusing the existing allocation
+    ///
+    /// The shift must be less than a word. Instead of growing the array,
+    /// the bits shifted out of the most significant word are returned,
+    /// so the result may have trailing zero words.
+    fn copy_shifted_from(&mut self, value: &Self, shift: u32) -> Result<Word, A::AllocErr> {
+        debug_assert!(shift < 64);
+        self.words.clear();
+        if self.words.capacity() < value.words.len() {
+            // Growing allocates like the value, since a new context may not know how
+            self.words = value.words.with_capacity_like(value.words.len())?;
+        }
+        let mut carry = 0u64;
+        for &word in value.words() {
+            unsafe { self.words.unchecked_push(Word((word.0 << shift) | carry)) };
+            carry = if shift == 0 { 0 } else { word.0 >> (64 - shift) };
+        }
+        Ok(Word(carry))
+    }
+    /// Raise this integer to the specified power, modulo `modulus`
+    ///
+    /// Every intermediate result is reduced modulo `modulus`,
+    /// so memory usage is bounded regardless of the size of the exponent.
+    ///
+    /// Odd moduli use Montgomery reduction, which never needs
+    /// more words than the modulus. Even moduli reduce each product
+    /// with [UnsignedInteger::mul_mod], so a fixed-capacity array
+    /// needs room for twice the length of the modulus.
+    /// To exponentiate repeatedly with the same odd modulus,
+    /// reuse a [MontgomeryCtx] with [UnsignedInteger::pow_mod_mont].
+    ///
+    /// Errors with [PowError::ZeroModulus] if the modulus is zero,
+    /// or if allocating space fails
+    pub fn pow_mod(&self, exponent: &Self, modulus: &Self) -> Result<Self, PowError<A::AllocErr>> {
+        if modulus.words().is_empty() {
+            return Err(PowError::ZeroModulus);
+        }
+        if modulus.is_odd() && !modulus.eq_u64(1) {
+            let ctx = match MontgomeryCtx::new(modulus) {
+                Ok(ctx) => ctx,
+                Err(MontgomeryError::AllocFailed { cause }) => return Err(PowError::AllocFailed { cause }),
+                Err(MontgomeryError::EvenModulus) => unreachable!("Odd modulus")
+            };
+            return Ok(self.pow_mod_mont(exponent, &ctx)?);
+        }
+        let (_, base) = self.div_rem(modulus)?;
+        // Even for a zero exponent, the result must be reduced (`1 mod 1 == 0`)
+        let mut res = self.with_capacity_like(1)?;
+        res.set_one()?;
+        if modulus.words() == [Word(1)] {
+            res.words.clear();
+        }
+        /*
+         * Left-to-right binary exponentiation (square-and-multiply),
+         * reading the exponent a bit at a time.
+         */
+        for index in (0..exponent.bit_len()).rev() {
+            res = res.mul_mod(&res, modulus)?;
+            if exponent.get_bit(index) {
+                res = res.mul_mod(&base, modulus)?;
+            }
+        }
+        Ok(res)
+    }
+    /// Raise this integer to the specified power modulo `modulus`,
+    /// unless that would take more than `max_squarings` squarings
+    ///
+    /// Square-and-multiply squares once per bit of the exponent,
+    /// so the limit is checked against its bit length up front, before doing any work.
+    /// This protects services accepting untrusted exponents
+    /// from being made to do arbitrarily expensive exponentiations.
+    /// Otherwise, this is the same as [UnsignedInteger::pow_mod].
+    ///
+    /// Errors with [PowError::ExponentTooLarge] if the exponent has more than `max_squarings` bits,
+    /// with [PowError::ZeroModulus] if the modulus is zero,
+    /// or if allocating space fails
+    pub fn modpow_limited(&self, exponent: &Self, modulus: &Self, max_squarings: u64) -> Result<Self, PowError<A::AllocErr>> {
+        let bits = exponent.bit_len();
+        if bits > max_squarings {
+            return Err(PowError::ExponentTooLarge { bits, max_squarings });
+        }
+        self.pow_mod(exponent, modulus)
+    }
+    /// Multiply this integer by the specified integer, modulo `modulus`
+    ///
+    /// The result is always less than the modulus.
+    /// The full product is computed before reducing it,
+    /// so the array must have room for `self.len() + other.len()` words.
+    ///
+    /// Errors with [DivError::DivisionByZero] if the modulus is zero,
+    /// or if allocating space fails
+    pub fn mul_mod(&self, other: &Self, modulus: &Self) -> Result<Self, DivError<A::AllocErr>> {
+        if modulus.words().is_empty() {
+            return Err(DivError::DivisionByZero);
+        }
+        let mut product = self.with_capacity_like(0)?;
+        self.mul_into(other, &mut product)?;
+        Ok(product.div_rem(modulus)?.1)
+    }
+    /// The integer square root of this integer, `floor(sqrt(self))`
+    ///
+    /// Errors if allocating space fails
+    pub fn isqrt(&self) -> Result<Self, A::AllocErr> {
+        if self.bit_len() <= 1 {
+            // Zero and one are their own square roots
+            return self.clone_with_capacity(0);
+        }
+        /*
+         * Newton's method: `x = (x + self / x) / 2`
+         *
+         * Starting from a power of two that is at least the square root,
+         * every iteration decreases until reaching `floor(sqrt(self))`,
+         * after which the next iteration would no longer decrease.
+         */
+        let mut x = self.with_capacity_like(1)?;
+        x.set_one()?;
+        x.shl_assign(shift_bits(self.bit_len().divide_round_up(2)))?;
+        loop {
+            let mut next = match self.div_rem(&x) {
+                Ok((quotient, _)) => quotient,
+                Err(DivError::AllocFailed { cause }) => return Err(cause),
+                Err(DivError::DivisionByZero) => unreachable!()
+            };
+            UnsignedInteger::add(&mut next, &x)?;
+            next.shr_assign(1);
+            if next >= x {
+                break;
+            }
+            x = next;
+        }
+        Ok(x)
+    }
+    /// The greatest common divisor of this integer and the specified integer
+    ///
+    /// The GCD of zero and `n` is `n`, so both being zero gives zero.
+    ///
+    /// Once both operands have at least [LEHMER_GCD_THRESHOLD] words,
+    /// this uses Lehmer's algorithm to shrink them,
+    /// finishing with the binary algorithm once they're small.
+    ///
+    /// Errors if allocating space fails
+    pub fn gcd(&self, other: &Self) -> Result<Self, A::AllocErr> {
+        if self.words.len().min(other.words.len()) >= LEHMER_GCD_THRESHOLD {
+            self.gcd_lehmer(other)
+        } else {
+            self.gcd_binary(other)
+        }
+    }
+    /// The greatest common divisor, using Lehmer's algorithm
+    ///
+    /// Each round runs the Euclidean algorithm on just the top word of each operand,
+    /// tracking the combined effect of all those steps as a matrix of single-word cofactors.
+    /// Applying the matrix to the full operands then takes the place
+    /// of many full-precision divisions (Knuth, TAOCP Vol 2, 4.5.2, Algorithm L).
+    fn gcd_lehmer(&self, other: &Self) -> Result<Self, A::AllocErr> {
+        let (mut a, mut b) = if self >= other {
+            (self.clone_with_capacity(0)?, other.clone_with_capacity(0)?)
+        } else {
+            (other.clone_with_capacity(0)?, self.clone_with_capacity(0)?)
+        };
+        // Neither operand ever grows, so the scratch space is allocated once
+        let len = a.words.len();
+        let mut next_a = a.with_capacity_like(len)?;
+        let mut next_b = a.with_capacity_like(len)?;
+        while b.words.len() >= LEHMER_GCD_THRESHOLD {
+            // The top word of `a`, and the bits of `b` at the same position
+            let shift = a.bit_len() - Word::BITS;
+            let (mut a_top, mut b_top) = (top_word(a.words(), shift) as i128, top_word(b.words(), shift) as i128);
+            let (mut x0, mut y0, mut x1, mut y1) = (1i128, 0i128, 0i128, 1i128);
+            loop {
+                /*
+                 * The top words only determine the quotient
+                 * if both extremes of the cofactors give the same one.
+                 */
+                let (low_divisor, high_divisor) = (b_top + x1, b_top + y1);
+                if low_divisor <= 0 || high_divisor <= 0 {
+                    break;
+                }
+                let quotient = (a_top + x0) / low_divisor;
+                if quotient != (a_top + y0) / high_divisor {
+                    break;
+                }
+                let next = (x1, x0 - quotient * x1);
+                x0 = next.0;
+                x1 = next.1;
+                let next = (y1, y0 - quotient * y1);
+                y0 = next.0;
+                y1 = next.1;
+                let next = (b_top, a_top - quotient * b_top);
+                a_top = next.0;
+                b_top = next.1;
+            }
+            if y0 == 0 {
+                // The top words alone couldn't make progress, so take a full Euclidean step
+                let remainder = match a.div_rem(&b) {
+                    Ok((_, remainder)) => remainder,
+                    Err(DivError::AllocFailed { cause }) => return Err(cause),
+                    Err(DivError::DivisionByZero) => unreachable!("Nonzero divisor")
+                };
+                a = core::mem::replace(&mut b, remainder);
+                continue;
+            }
+            // `a, b = x0 * a + y0 * b, x1 * a + y1 * b`, where each pair has opposite signs
+            apply_cofactors(&mut next_a, &a, &b, x0, y0);
+            apply_cofactors(&mut next_b, &a, &b, x1, y1);
+            core::mem::swap(&mut a, &mut next_a);
+            core::mem::swap(&mut b, &mut next_b);
+        }
+        a.gcd_binary(&b)
+    }
+    /// The greatest common divisor, using Stein's binary algorithm
+    fn gcd_binary(&self, other: &Self) -> Result<Self, A::AllocErr> {
+        let (mut a, mut b) = (self.clone_with_capacity(0)?, other.clone_with_capacity(0)?);
+        let a_zeros = match a.trailing_zeros() {
+            Some(zeros) => zeros,
+            None => return Ok(b)
+        };
+        let b_zeros = match b.trailing_zeros() {
+            Some(zeros) => zeros,
+            None => return Ok(a)
+        };
+        /*
+         * Stein's binary GCD algorithm:
+         * Factor out the common powers of two, then keep both values odd
+         * by repeatedly subtracting the smaller from the larger
+         * and shifting out the (newly even) difference's factors of two.
+         */
+        let common_zeros = a_zeros.min(b_zeros);
+        a.shr_assign(shift_bits(a_zeros));
+        b.shr_assign(shift_bits(b_zeros));
+        loop {
+            if a > b {
+                core::mem::swap(&mut a, &mut b);
+            }
+            b.unchecked_sub(&a);
+            match b.trailing_zeros() {
+                Some(zeros) => b.shr_assign(shift_bits(zeros)),
+                None => break
+            }
+        }
+        a.shl_assign(shift_bits(common_zeros))?;
+        Ok(a)
+    }
+    /// Divide the specified factor out of this integer
+    /// as many times as it evenly divides it, returning that multiplicity
+    ///
+    /// The remaining cofactor is left in this integer.
+    /// Dividing out zero or one would never finish, so those factors
+    /// leave this integer unchanged and return zero.
+    /// Zero is also left unchanged (returning zero),
+    /// since every factor divides it infinitely often.
+    ///
+    /// Errors if allocating space fails, in which case this integer
+    /// is left with the occurrences removed so far divided out.
+    pub fn remove_factor(&mut self, factor: &Self) -> Result<u32, A::AllocErr> {
+        if factor.bit_len() <= 1 || self.is_empty() {
+            return Ok(0);
+        }
+        if let Some(shift) = factor.power_of_two_exponent() {
+            // Removing a power of two is just a shift
+            let count = self.trailing_zeros().unwrap() / shift as u64;
+            UnsignedInteger::shr_assign(self, shift_bits(count * shift as u64));
+            return Ok(count as u32);
+        }
+        /*
+         * Divide into a separate quotient, and only swap it in if the division was exact.
+         * The old value's array then holds the next quotient,
+         * so after the first division nothing needs to allocate.
+         */
+        let mut ctx = DivContext::new();
+        let mut quotient = self.with_capacity_like(0)?;
+        let mut count = 0;
+        while self.cmp_magnitude(factor) != Ordering::Less {
+            let exact = if factor.len() == 1 {
+                quotient.copy_shifted_from(self, 0)?;
+                // Can't fail, since the factor is nonzero and division never allocates
+                quotient.div_rem_u64(factor.words()[0].0).unwrap() == 0
+            } else {
+                self.div_rem_knuth_into(factor, &mut ctx, &mut quotient)?;
+                ctx.remainder.is_empty()
+            };
+            if !exact {
+                break;
+            }
+            core::mem::swap(self, &mut quotient);
+            count += 1;
+        }
+        Ok(count)
+    }
+    /// Check if this integer and the specified integer are coprime,
+    /// meaning their greatest common divisor is one
+    ///
+    /// Two even integers always share a factor of two,
+    /// so that case is answered without computing the GCD.
+    /// Zero is only coprime to one.
+    ///
+    /// Errors if allocating space fails
+    pub fn is_coprime(&self, other: &Self) -> Result<bool, A::AllocErr> {
+        if self.is_even() && other.is_even() {
+            return Ok(false);
+        }
+        Ok(self.gcd(other)?.eq_u64(1))
+    }
+    /// Decompose `self - 1 = d * 2**s` with `d` odd, for the Miller-Rabin test,
+    /// returning `(self - 1, d, s)`
+    ///
+    /// This integer must be odd and greater than one.
+    #[cfg(any(feature = "alloc", feature = "rand"))]
+    pub(crate) fn miller_rabin_decompose(&self) -> Result<(Self, Self, u64), A::AllocErr> {
+        debug_assert!(self.is_odd() && !self.eq_u64(1));
+        // Since we're odd, subtracting one just clears the low bit
+        let mut n_minus_one = self.clone_with_capacity(0)?;
+        n_minus_one.set_bit(0, false)?;
+        let s = n_minus_one.trailing_zeros().unwrap();
+        let mut d = n_minus_one.clone_with_capacity(0)?;
+        UnsignedInteger::shr_assign(&mut d, u32::try_from(s)
+            .map_err(|_| A::AllocErr::capacity_arithmetic_overflow())?);
+        Ok((n_minus_one, d, s))
+    }
+    /// Run a single round of the Miller-Rabin test with the specified witness in `[2, self - 2]`,
+    /// given the decomposition from [UnsignedInteger::miller_rabin_decompose]
+    ///
+    /// Returns false if the witness proves this integer composite.
+    #[cfg(any(feature = "alloc", feature = "rand"))]
+    pub(crate) fn miller_rabin_round(&self, witness: &Self, n_minus_one: &Self, d: &Self, s: u64) -> Result<bool, A::AllocErr> {
+        let mut x = witness.pow_mod(d, self).map_err(alloc_failure)?;
+        if x.eq_u64(1) || x == *n_minus_one {
+            return Ok(true);
+        }
+        for _ in 1..s {
+            x = x.mul_mod(&x, self).map_err(|cause| alloc_failure(cause.into()))?;
+            if x == *n_minus_one {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+    /// Divide this integer by the specified [u64] in place,
+    /// returning the remainder
+    ///
+    /// This is much cheaper than dividing by a full integer.
+    ///
+    /// Errors with [DivError::DivisionByZero] if the divisor is zero,
+    /// in which case this integer is left unchanged.
+    #[inline]
+    pub fn div_rem_u64(&mut self, divisor: u64) -> Result<u64, DivError<A::AllocErr>> {
+        if divisor == 0 {
+            return Err(DivError::DivisionByZero);
+        }
+        let remainder = div_rem_word_assign(self.words.as_mut(), divisor);
+        // Only the top word can become zero
+        if let Some(Word(0)) = self.words().last() {
+            self.words.truncate(self.words.len() - 1);
+        }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+        Ok(remainder)
+    }
+    /// Multiply this integer by the specified [u64]
+    ///
+    /// This is much cheaper than multiplying by a full integer.
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn mul_u64(&mut self, val: u64) -> Result<(), A::AllocErr> {
+        match val {
+            0 => {
+                self.words.clear();
+                return Ok(());
+            },
+            1 => return Ok(()),
+            _ => {}
+        }
+        // A full array only needs to grow if the product actually carries into a new word
+        if self.words.len() < self.words.capacity() || mul_carry(self.words(), val) != 0 {
+            self.words.reserve(1)?;
+        }
+        unsafe { self.unchecked_mul_u64(val) };
+        Ok(())
+    }
+    /// Multiply this integer by the specified [u64],
+    /// without checking for the right capacity.
+    ///
+    /// ## Safety
+    /// Assumes `self.words.capacity >= self.words.len + 1`,
+    /// as if calling `self.words.reserve(1)`
+    #[inline]
+    pub unsafe fn unchecked_mul_u64(&mut self, val: u64) {
+        if val == 0 {
+            self.words.clear();
+        } else {
+            self.unchecked_mul_add_u64(val, 0);
+        }
+    }
+    /// Multiply this integer by the specified [u64], then add `addend`,
+    /// without checking for the right capacity
+    ///
+    /// This is a single step of Horner's method.
+    ///
+    /// ## Safety
+    /// Assumes `self.words.capacity >= self.words.len + 1`
+    pub(crate) unsafe fn unchecked_mul_add_u64(&mut self, multiplier: u64, addend: u64) {
+        // A zero multiplier would leave trailing zero words
+        debug_assert_ne!(multiplier, 0);
+        let carry = mul_add_small_assign(self.words.as_mut(), multiplier, addend);
+        if carry != 0 {
+            unsafe { self.words.unchecked_push(Word(carry)) };
+        }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+    }
+    /// Multiply this integer by the specified [u64], then add `addend`,
+    /// only growing if the result actually needs another word
+    ///
+    /// This is a single step of Horner's method,
+    /// for when the final length isn't known up front.
+    ///
+    /// Errors if allocating space fails,
+    /// in which case this integer is left holding the low words of the result.
+    pub(crate) fn mul_add_u64(&mut self, multiplier: u64, addend: u64) -> Result<(), A::AllocErr> {
+        // A zero multiplier would leave trailing zero words
+        debug_assert_ne!(multiplier, 0);
+        let carry = mul_add_small_assign(self.words.as_mut(), multiplier, addend);
+        if carry != 0 {
+            self.words.push(Word(carry))?;
+        }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+        Ok(())
+    }
+    /// Increment this integer by one
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn inc(&mut self) -> Result<(), A::AllocErr> {
+        self.add_u64(1)
+    }
+    /// Decrement this integer by one
+    ///
+    /// This never allocates, and usually only touches the low word.
+    ///
+    /// Errors with [SubError::Underflow] if this integer is zero
+    #[inline]
+    pub fn dec(&mut self) -> Result<(), SubError<A::AllocErr>> {
+        self.sub_u64(1)
+    }
+    /// Subtract the specified [u64] from this integer
+    ///
+    /// Errors with [SubError::Underflow] if the value is larger than this integer,
+    /// in which case this integer is left unchanged.
+    #[inline]
+    pub fn sub_u64(&mut self, val: u64) -> Result<(), SubError<A::AllocErr>> {
+        if self.cmp_u64(val) == Ordering::Less {
+            return Err(SubError::Underflow);
+        }
+        self.unchecked_sub_u64(val);
+        Ok(())
+    }
+    /// Subtract the specified [u64] from this integer,
+    /// without checking for underflow
+    ///
+    /// Assumes `self >= val`. Subtraction never needs to allocate,
+    /// so violating this can't cause undefined behavior,
+    /// but the result is meaningless (and panics with debug assertions).
+    pub fn unchecked_sub_u64(&mut self, val: u64) {
+        let mut subtrahend = val;
+        // Each borrow only takes one from the next word
+        for word in self.words.as_mut() {
+            if subtrahend == 0 {
+                break;
+            }
+            let (res, borrow) = word.0.overflowing_sub(subtrahend);
+            word.0 = res;
+            subtrahend = borrow as u64;
+        }
+        debug_assert_eq!(subtrahend, 0, "Subtraction underflowed");
+        // Only the top word can become zero
+        if let Some(Word(0)) = self.words().last() {
+            self.words.truncate(self.words.len() - 1);
+        }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+    }
+    /// Add the specified [u64] to this integer
+    #[inline]
+    pub fn add_u64(&mut self, val: u64) -> Result<(), A::AllocErr> {
+        // Only grow if the sum actually carries into a new word
+        let carries = match self.words().split_first() {
+            None => val != 0,
+            Some((low, high)) => low.0.checked_add(val).is_none()
+                && high.iter().all(|word| word.0 == u64::MAX)
+        };
+        if carries {
+            self.words.reserve(1)?;
+        }
+        unsafe { self.unchecked_add_u64(val) }
+        Ok(())
+    }
+    /// Add the specified [u64] to this integer,
+    /// without checking for the right capacity.
+    ///
+    /// ## Safety
+    /// Assumes `self.words.capacity >= self.words.len + 1`,
+    /// as if calling `self.words.reserve(1)`
+    #[inline]
+    pub unsafe fn unchecked_add_u64(&mut self, val: u64) {
+        self.unchecked_carrying_add_u64(val, false);
+    }
+    /// Add the specified [u64] and an incoming carry to this integer
+    ///
+    /// Returns whether the sum carried out of the most significant word,
+    /// growing this integer by an extra word (zero is treated as a single zero word).
+    /// This is the primitive for chaining scalar additions across word boundaries.
+    ///
+    /// Errors if allocating space fails
+    #[inline]
+    pub fn carrying_add_u64(&mut self, val: u64, carry_in: bool) -> Result<bool, A::AllocErr> {
+        // Only grow by the words the sum actually needs, like `add_u64`
+        let additional = match self.words().split_first() {
+            None if val == 0 && !carry_in => 0,
+            None => 1 + (val == u64::MAX && carry_in) as usize,
+            Some((low, high)) => {
+                let carries = low.0 as u128 + val as u128 + carry_in as u128 > u64::MAX as u128
+                    && high.iter().all(|word| word.0 == u64::MAX);
+                carries as usize
+            }
+        };
+        if additional > 0 {
+            self.words.reserve(additional)?;
+        }
+        Ok(unsafe { self.unchecked_carrying_add_u64(val, carry_in) })
+    }
+    /// Add the specified [u64] and an incoming carry to this integer,
+    /// without checking for the right capacity
+    ///
+    /// ## Safety
+    /// Assumes `self.words.capacity >= max(self.words.len, 1) + 1`.
+    /// If `carry_in` is false, `self.words.len + 1` is sufficient.
+    unsafe fn unchecked_carrying_add_u64(&mut self, val: u64, carry_in: bool) -> bool {
+        if self.words.len() == 0 {
+            if val == 0 && !carry_in {
+                return false;
+            }
+            unsafe { self.words.unchecked_push(Word(0)) };
+        }
+        let mut addend = val;
+        let mut carry = carry_in;
+        for target_word in self.words.as_mut().iter_mut() {
+            let (res, first_carry) = target_word.0.overflowing_add(addend);
+            let (res, second_carry) = res.overflowing_add(carry as u64);
+            target_word.0 = res;
+            // At most one of the additions can carry
+            carry = first_carry | second_carry;
+            addend = 0;
+            if !carry {
+                break;
+            }
+        }
+        if carry {
+            unsafe { self.words.unchecked_push(Word(1)) };
+        }
+        debug_assert_eq!(self.check_invariants(), Ok(()));
+        carry
+    }
+}
+impl<A: WordArray> Num for UnsignedInteger<A> {
+    type FromStrRadixErr = ParseIntError<A::AllocErr>;
+    /// Parse a string in the specified radix (base)
+    ///
+    /// Surrounding ASCII whitespace is ignored,
+    /// but whitespace between digits is still an error.
+    #[inline]
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, ParseIntError<A::AllocErr>> {
+        crate::string::parse_unsigned_radix(str.trim_matches(|c: char| c.is_ascii_whitespace()), radix)
+    }
+}
+
+/// Sort a slice of integers in ascending order
+///
+/// This is an unstable sort taking `O(n log n)` comparisons.
+/// Each comparison is cheap, since integers with different
+/// numbers of words are ordered by length alone,
+/// and only equal-length integers need to compare words.
+#[inline]
+pub fn sort_unsigned<A: WordArray>(values: &mut [UnsignedInteger<A>]) {
+    values.sort_unstable_by(|left, right| left.cmp_magnitude(right));
+}
+
+/// An integer with an unsigned magnitude (absolute value)
+///
+/// This lets [sort_by_magnitude] sort both signed and unsigned integers.
+pub trait Magnitude {
+    /// The array of words holding the magnitude
+    type Array: WordArray;
+    /// The absolute value of this integer
+    fn magnitude(&self) -> &UnsignedInteger<Self::Array>;
+}
+impl<A: WordArray> Magnitude for UnsignedInteger<A> {
+    type Array = A;
+    #[inline]
+    fn magnitude(&self) -> &UnsignedInteger<A> {
+        self
+    }
+}
+
+/// Sort a slice of integers by their magnitudes, ignoring any signs
+///
+/// Like [sort_unsigned], this is an unstable sort with cheap comparisons.
+/// Integers with the same magnitude, like `-3` and `3`,
+/// may end up in either order.
+#[inline]
+pub fn sort_by_magnitude<T: Magnitude>(values: &mut [T]) {
+    values.sort_unstable_by(|left, right| left.magnitude().cmp_magnitude(right.magnitude()));
+}
+
+/// Extract the allocation failure from an error,
+/// where the modulus is known to be nonzero
+#[cfg(any(feature = "alloc", feature = "rand"))]
+pub(crate) fn alloc_failure<E: IAllocError>(cause: PowError<E>) -> E {
+    match cause {
+        PowError::AllocFailed { cause } => cause,
+        PowError::ZeroModulus | PowError::ExponentTooLarge { .. } => unreachable!("Nonzero modulus, without a limit")
+    }
+}
+
+/// Multiply `src` by `scalar`, adding the product to the low words of `target`
+///
+/// Computes `target += src * scalar` over the first `src.len()` words of `target`,
+/// returning the carry word that belongs at `target[src.len()]`.
+/// The carry is returned rather than propagated, so any higher words of `target`
+/// are left untouched.
+///
+/// This is the inner kernel of grade school multiplication.
+///
+/// ## Safety
+/// Undefined behavior if `target.len() < src.len()`
+#[inline]
+pub unsafe fn mul_add_word_assign(target: &mut [Word], src: &[Word], scalar: Word) -> Word {
+    debug_assert!(target.len() >= src.len());
+    let mut carry = 0u64;
+    for (index, multiplicand) in src.iter().enumerate() {
+        let target_word = unsafe { target.get_unchecked_mut(index) };
+        /*
+         * Since `(2**64 - 1)**2 + 2 * (2**64 - 1) == 2**128 - 1`,
+         * the product of two words plus the existing word and carry always fits in a u128.
+         */
+        let wide = (scalar.0 as u128) * (multiplicand.0 as u128)
+            + (target_word.0 as u128)
+            + (carry as u128);
+        target_word.0 = wide as u64;
+        carry = (wide >> 64) as u64;
+    }
+    Word(carry)
+}
+
+/// The number of words both operands must have
+/// before [UnsignedInteger::gcd] switches from the binary algorithm to Lehmer's
+///
+/// The `gcd` benchmark compares both algorithms.
+pub const LEHMER_GCD_THRESHOLD: usize = 12;
+
+/// The 64 bits of the specified words starting at bit `shift`
+fn top_word(words: &[Word], shift: u64) -> u64 {
+    let (index, bit_shift) = ((shift / Word::BITS) as usize, shift % Word::BITS);
+    let low = words.get(index).map_or(0, |word| word.0 >> bit_shift);
+    let high = match words.get(index + 1) {
+        Some(word) if bit_shift != 0 => word.0 << (Word::BITS - bit_shift),
+        _ => 0
+    };
+    low | high
+}
+
+/// Compute `x * a + y * b` into `target`, for cofactors of opposite signs
+/// from a round of Lehmer's algorithm
+///
+/// The cofactors fit in a word, and the result is nonnegative
+/// and no longer than `a`, which is at least as long as `b`.
+fn apply_cofactors<A: WordArray>(target: &mut UnsignedInteger<A>, a: &UnsignedInteger<A>, b: &UnsignedInteger<A>, x: i128, y: i128) {
+    debug_assert!((x >= 0) != (y >= 0) || x == 0 || y == 0);
+    // Add the positive term, and subtract the negative one
+    let ((added, added_scalar), (subtracted, subtracted_scalar)) = if y <= 0 {
+        ((a.words(), x as u64), (b.words(), (-y) as u64))
+    } else {
+        ((b.words(), y as u64), (a.words(), (-x) as u64))
+    };
+    let len = a.words().len();
+    target.words.clear();
+    let (mut add_carry, mut sub_carry, mut borrow) = (0u64, 0u64, false);
+    for index in 0..len {
+        let word = |words: &[Word]| words.get(index).map_or(0, |word| word.0) as u128;
+        let sum = word(added) * added_scalar as u128 + add_carry as u128;
+        let difference = word(subtracted) * subtracted_scalar as u128 + sub_carry as u128;
+        add_carry = (sum >> 64) as u64;
+        sub_carry = (difference >> 64) as u64;
+        let (res, first_borrow) = (sum as u64).overflowing_sub(difference as u64);
+        let (res, second_borrow) = res.overflowing_sub(borrow as u64);
+        borrow = first_borrow || second_borrow;
+        // The target has as much capacity as `a`
+        unsafe { target.words.unchecked_push(Word(res)) };
+    }
+    debug_assert_eq!(add_carry as u128, sub_carry as u128 + borrow as u128, "Negative or overlong combination");
+    target.trim();
+}
+
+/// The number of quotient bits below which division
+/// subtracts shifted copies of the divisor, instead of using Algorithm D
+///
+/// Each bit costs a comparison, subtraction and shift of the whole remainder,
+/// so this only pays off for tiny quotients.
+/// The `small_quotient` benchmark compares both algorithms.
+/// See [UnsignedInteger::div_rem_small_quotient].
+pub const SMALL_QUOTIENT_BITS: u64 = 2;
+
+/// The number of words both operands must have
+/// before multiplication switches to Karatsuba's algorithm
+///
+/// Below this, grade school multiplication is faster.
+/// Without the `alloc` feature, Karatsuba's algorithm is never used.
+/// This is the default for [MulConfig], which can override it.
+pub const KARATSUBA_THRESHOLD: usize = 32;
+
+/// The smallest Karatsuba threshold a [MulConfig] accepts
+///
+/// Splitting fewer words doesn't make the middle product any shorter,
+/// so the recursion would never reach grade school multiplication.
+const MIN_KARATSUBA_THRESHOLD: usize = 4;
+
+/// Tuning parameters for [UnsignedInteger::mul_with]
+///
+/// The best thresholds depend on the machine,
+/// so the `multiplication` benchmark compares them.
+/// The defaults are used by [UnsignedInteger::mul] and the operators.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MulConfig {
+    karatsuba_threshold: usize
+}
+impl MulConfig {
+    /// The default configuration, with a threshold of [KARATSUBA_THRESHOLD]
+    pub const DEFAULT: MulConfig = MulConfig { karatsuba_threshold: KARATSUBA_THRESHOLD };
+    /// Create the default configuration
+    #[inline]
+    pub const fn new() -> Self {
+        MulConfig::DEFAULT
+    }
+    /// Use Karatsuba's algorithm once both operands have at least the specified number of words
+    ///
+    /// Thresholds below four are raised to four.
+    /// Passing [usize::MAX] always uses grade school multiplication.
+    #[inline]
+    pub const fn with_karatsuba_threshold(self, words: usize) -> Self {
+        let karatsuba_threshold = if words < MIN_KARATSUBA_THRESHOLD { MIN_KARATSUBA_THRESHOLD } else { words };
+        MulConfig { karatsuba_threshold }
+    }
+    /// The number of words both operands need before using Karatsuba's algorithm
+    #[inline]
+    pub const fn karatsuba_threshold(&self) -> usize {
+        self.karatsuba_threshold
+    }
+}
+impl Default for MulConfig {
+    #[inline]
+    fn default() -> Self {
+        MulConfig::DEFAULT
+    }
+}
+
+/// Multiply the specified words, writing the product into `target`
+///
+/// Requires `target.len() == left.len() + right.len()`,
+/// and that `target` is zeroed beforehand.
+fn mul_words(target: &mut [Word], left: &[Word], right: &[Word], config: &MulConfig) {
+    let (left, right) = if left.len() >= right.len() { (left, right) } else { (right, left) };
+    debug_assert_eq!(target.len(), left.len() + right.len());
+    #[cfg(feature = "alloc")]
+    {
+        if right.len() >= config.karatsuba_threshold {
+            return karatsuba_mul_words(target, left, right, config);
+        }
+    }
+    #[cfg(not(feature = "alloc"))]
+    let _ = config;
+    /*
+     * Grade school multiplication: Multiply the entirety of `left`
+     * by each word of `right`, accumulating each row into the product
+     * at the offset of the word.
+     */
+    for (offset, &multiplier) in right.iter().enumerate() {
+        // Each row has room for `left.len() + 1` words, since `offset < right.len()`
+        let carry = unsafe { mul_add_word_assign(&mut target[offset..], left, multiplier) };
+        target[offset + left.len()] = carry;
+    }
+}
+
+/// Multiply the specified words using Karatsuba's algorithm,
+/// writing the product into `target`
+///
+/// This needs scratch space, so it is only available with the `alloc` feature.
+/// Requires `right.len() <= left.len()`, in addition to the requirements of [mul_words].
+#[cfg(feature = "alloc")]
+fn karatsuba_mul_words(target: &mut [Word], left: &[Word], right: &[Word], config: &MulConfig) {
+    debug_assert!(right.len() <= left.len());
+    if right.len() * 2 <= left.len() {
+        /*
+         * Splitting such unbalanced operands in half would waste most of the work,
+         * so multiply `right` by each chunk of `left` that is the same size.
+         */
+        let mut product = vec![Word(0); right.len() * 2];
+        for (index, chunk) in left.chunks(right.len()).enumerate() {
+            let product = &mut product[..chunk.len() + right.len()];
+            for word in product.iter_mut() {
+                *word = Word(0);
+            }
+            mul_words(product, chunk, right, config);
+            let carry = add_words_assign(&mut target[index * right.len()..], product);
+            debug_assert!(!carry);
+        }
+    } else {
+        /*
+         * Karatsuba's algorithm: Split both operands at `half` words,
+         * so `left = left_high * B + left_low` (and the same for `right`).
+         * Then the product only needs three half-size multiplications:
+         *
+         * low = left_low * right_low
+         * high = left_high * right_high
+         * middle = (left_low + left_high) * (right_low + right_high) - low - high
+         * product = high * B**2 + middle * B + low
+         */
+        let half = left.len() / 2;
+        let (left_low, left_high) = left.split_at(half);
+        let (right_low, right_high) = right.split_at(half);
+        let (low, high) = target.split_at_mut(half * 2);
+        mul_words(low, left_low, right_low, config);
+        mul_words(high, left_high, right_high, config);
+        let (left_sum, right_sum) = (sum_words(left_low, left_high), sum_words(right_low, right_high));
+        let mut middle = vec![Word(0); left_sum.len() + right_sum.len()];
+        mul_words(&mut middle, &left_sum, &right_sum, config);
+        let borrow = sub_words_assign(&mut middle, &target[..half * 2])
+            | sub_words_assign(&mut middle, &target[half * 2..]);
+        debug_assert!(!borrow);
+        // The middle product is smaller than its buffer, so drop the extra zero words
+        let len = normalized_len(&middle);
+        let carry = add_words_assign(&mut target[half..], &middle[..len]);
+        debug_assert!(!carry);
+    }
+}
+
+/// The sum of the specified words, which may have a trailing zero word
+#[cfg(feature = "alloc")]
+fn sum_words(left: &[Word], right: &[Word]) -> Vec<Word> {
+    let (longer, shorter) = if left.len() >= right.len() { (left, right) } else { (right, left) };
+    let mut res = Vec::with_capacity(longer.len() + 1);
+    res.extend_from_slice(longer);
+    res.push(Word(0));
+    let carry = add_words_assign(&mut res, shorter);
+    debug_assert!(!carry);
+    res
+}
+
+/// Add `src` to the words of `target`, propagating carries
+/// through the remaining words of `target`
+///
+/// Returns the carry out of the most significant word of `target`.
+/// Requires `target.len() >= src.len()`.
+#[cfg(feature = "alloc")]
+fn add_words_assign(target: &mut [Word], src: &[Word]) -> bool {
+    debug_assert!(target.len() >= src.len());
+    let (low, high) = target.split_at_mut(src.len());
+    let mut carry = false;
+    for (target_word, addend) in low.iter_mut().zip(src) {
+        let (res, first_carry) = target_word.0.overflowing_add(addend.0);
+        let (res, second_carry) = res.overflowing_add(carry as u64);
+        target_word.0 = res;
+        // At most one of the additions can carry
+        carry = first_carry | second_carry;
+    }
+    for target_word in high {
+        if !carry {
+            break;
+        }
+        let (res, new_carry) = target_word.0.overflowing_add(1);
+        target_word.0 = res;
+        carry = new_carry;
+    }
+    carry
+}
+
+/// Multiply `src` by `scalar`, subtracting the product from `target`
+/// followed by `top_word`
+///
+/// Returns whether the subtraction borrowed out of `top_word`,
+/// which indicates that the product was larger.
+/// Requires `target.len() == src.len()`.
+fn mul_sub_word_assign(target: &mut [Word], top_word: &mut Word, src: &[Word], scalar: Word) -> bool {
+    debug_assert_eq!(target.len(), src.len());
+    let mut carry = 0u64;
+    let mut borrow = false;
+    for (target_word, &src_word) in target.iter_mut().zip(src) {
+        // `(2**64 - 1)**2 + (2**64 - 1) < 2**128`, so this can't overflow
+        let product = (src_word.0 as u128) * (scalar.0 as u128) + (carry as u128);
+        carry = (product >> 64) as u64;
+        let (res, first_borrow) = target_word.0.overflowing_sub(product as u64);
+        let (res, second_borrow) = res.overflowing_sub(borrow as u64);
+        target_word.0 = res;
+        borrow = first_borrow | second_borrow;
+    }
+    let (res, first_borrow) = top_word.0.overflowing_sub(carry);
+    let (res, second_borrow) = res.overflowing_sub(borrow as u64);
+    top_word.0 = res;
+    first_borrow | second_borrow
+}
+
+/// Divide the specified words by a single word in place,
+/// from most significant to least, returning the remainder
+///
+/// May leave trailing zero words.
+fn div_rem_word_assign(words: &mut [Word], divisor: u64) -> u64 {
+    debug_assert_ne!(divisor, 0);
+    let mut remainder = 0u64;
+    for word in words.iter_mut().rev() {
+        // Since `remainder < divisor`, the quotient always fits in a word
+        let wide = ((remainder as u128) << 64) | (word.0 as u128);
+        word.0 = (wide / divisor as u128) as u64;
+        remainder = (wide % divisor as u128) as u64;
+    }
+    remainder
+}
+
+/// Multiply the specified words by a single word in place, then add `addend`,
+/// returning the carry word that belongs past the most significant word
+pub(crate) fn mul_add_small_assign(words: &mut [Word], multiplier: u64, addend: u64) -> u64 {
+    let mut carry = addend;
+    for word in words.iter_mut() {
+        // `(2**64 - 1)**2 + (2**64 - 1) < 2**128`, so this can't overflow
+        let wide = (word.0 as u128) * (multiplier as u128) + (carry as u128);
+        word.0 = wide as u64;
+        carry = (wide >> 64) as u64;
+    }
+    carry
+}
+
+/// The carry word out of multiplying the specified words by a single word,
+/// without actually modifying them
+fn mul_carry(words: &[Word], multiplier: u64) -> u64 {
+    let mut carry = 0u64;
+    for word in words {
+        let wide = (word.0 as u128) * (multiplier as u128) + (carry as u128);
+        carry = (wide >> 64) as u64;
+    }
+    carry
+}
+
+/// Subtract `src` from the words of `target`, propagating borrows
+/// through the remaining words of `target`
+///
+/// Returns the borrow out of the most significant word of `target`,
+/// which indicates that `src` was larger.
+/// Requires `target.len() >= src.len()`, and may leave trailing zero words.
+pub(crate) fn sub_words_assign(target: &mut [Word], src: &[Word]) -> bool {
+    debug_assert!(target.len() >= src.len());
+    let (low, high) = target.split_at_mut(src.len());
+    let mut borrow = false;
+    for (target_word, subtrahend) in low.iter_mut().zip(src) {
+        let (res, first_borrow) = target_word.0.overflowing_sub(subtrahend.0);
+        let (res, second_borrow) = res.overflowing_sub(borrow as u64);
+        target_word.0 = res;
+        // At most one of the subtractions can borrow
+        borrow = first_borrow | second_borrow;
+    }
+    for target_word in high {
+        if !borrow {
+            break;
+        }
+        let (res, new_borrow) = target_word.0.overflowing_sub(1);
+        target_word.0 = res;
+        borrow = new_borrow;
+    }
+    borrow
+}
+
+/// Convert a bit count into a shift amount,
+/// panicking if it is too large to shift by
+#[inline]
+fn shift_bits(bits: u64) -> u32 {
+    u32::try_from(bits).expect("Shift too large")
+}
+
+/// The number of significant bits in the specified words,
+/// which must not have any trailing zero words
+#[inline]
+pub(crate) fn bit_length(words: &[Word]) -> u64 {
+    match words.last() {
+        Some(top) => (words.len() as u64 - 1) * Word::BITS + (Word::BITS - top.0.leading_zeros() as u64),
+        None => 0
+    }
+}
+
+/// The length of the specified words,
+/// excluding any trailing zero words
+#[inline]
+fn normalized_len(words: &[Word]) -> usize {
+    words.iter().rposition(|word| word.0 != 0).map_or(0, |index| index + 1)
+}
+
+/// The number of words needed to hold the sum of the specified words,
+/// given the existing capacity of the target
+///
+/// The carry word is only counted if the sum actually needs it,
+/// so a full fixed-capacity array can still hold a sum that fits.
+/// When there's already room for a carry, this skips checking for one.
+fn sum_len(left: &[Word], right: &[Word], capacity: usize) -> usize {
+    let len = left.len().max(right.len());
+    if capacity > len || !add_overflows(left, right) {
+        len
+    } else {
+        len + 1
+    }
+}
+
+/// Check if adding the specified words would carry past
+/// the most significant word of the longer operand,
+/// without actually performing the addition
+fn add_overflows(left: &[Word], right: &[Word]) -> bool {
+    let (longer, shorter) = if left.len() >= right.len() {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    let mut carry = false;
+    for (index, word) in longer.iter().enumerate() {
+        let addend = shorter.get(index).map_or(0, |word| word.0);
+        let (res, first_carry) = word.0.overflowing_add(addend);
+        let (_, second_carry) = res.overflowing_add(carry as u64);
+        carry = first_carry | second_carry;
+    }
+    carry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InlineWordArray;
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+
+    fn random(rng: &mut SmallRng, len: usize) -> UnsignedInteger {
+        UnsignedInteger::from_words_iter((0..len).map(|_| Word(rng.gen()))).unwrap()
+    }
+
+    fn from_words(words: &[u64]) -> UnsignedInteger {
+        UnsignedInteger::from_words_iter(words.iter().map(|&word| Word(word))).unwrap()
+    }
+
+    fn small(value: u64) -> UnsignedInteger {
+        value.into()
+    }
+
+    fn check_div_rem(n: &UnsignedInteger, d: &UnsignedInteger) {
+        let (q, r) = n.div_rem(d).unwrap();
+        assert!(r < *d, "{:?} % {:?} gave {:?}", n, d, r);
+        let mut reconstructed = UnsignedInteger::mul(&q, d).unwrap();
+        UnsignedInteger::add(&mut reconstructed, &r).unwrap();
+        assert_eq!(reconstructed, *n, "{:?} / {:?}", n, d);
+    }
+
+    #[test]
+    fn div_rem_multi_word() {
+        let mut rng = SmallRng::seed_from_u64(265);
+        for n_len in 0..12 {
+            for d_len in 1..=n_len.max(1) + 1 {
+                for _ in 0..20 {
+                    let (n, d) = (random(&mut rng, n_len), random(&mut rng, d_len));
+                    if !d.is_empty() {
+                        check_div_rem(&n, &d);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn div_rem_edge_words() {
+        // Extreme words stress the quotient estimate and the add back step
+        const EDGES: [u64; 5] = [0, 1, u64::MAX, 1 << 63, (1 << 63) - 1];
+        for index in 0..EDGES.len().pow(4) {
+            let n = from_words(&[
+                EDGES[index % 5], EDGES[index / 5 % 5],
+                EDGES[index / 25 % 5], EDGES[index / 125 % 5]
+            ]);
+            for (&low, &high) in EDGES.iter().zip(EDGES.iter().rev()) {
+                let d = from_words(&[low, high]);
+                if d.len() == 2 {
+                    check_div_rem(&n, &d);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn div_rem_by_zero() {
+        let n = from_words(&[1, 2, 3]);
+        assert!(matches!(n.div_rem(&UnsignedInteger::ZERO), Err(DivError::DivisionByZero)));
+        assert!(matches!(
+            UnsignedInteger::<Vec<Word>>::ZERO.div_rem(&UnsignedInteger::ZERO),
+            Err(DivError::DivisionByZero)
+        ));
+    }
+
+    #[test]
+    fn div_rem_full_inline_array() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        for d_len in 2..=4 {
+            for _ in 0..100 {
+                let (n, d) = (random(&mut rng, 4), random(&mut rng, d_len));
+                let (q, r) = n.div_rem(&d).unwrap();
+                let inline_n: UnsignedInteger<InlineWordArray<4>> = n.convert().unwrap();
+                let inline_d: UnsignedInteger<InlineWordArray<4>> = d.convert().unwrap();
+                let (inline_q, inline_r) = inline_n.div_rem(&inline_d).unwrap();
+                assert_eq!(inline_q.words(), q.words());
+                assert_eq!(inline_r.words(), r.words());
+            }
+        }
+    }
+
+    #[test]
+    fn div_context_reused() {
+        let mut rng = SmallRng::seed_from_u64(296);
+        let mut ctx = DivContext::new();
+        for index in 0..10_000 {
+            let n_len = 1 + index % 9;
+            let d_len = 1 + index % 5;
+            let (n, d) = (random(&mut rng, n_len), random(&mut rng, d_len));
+            if d.is_empty() {
+                continue;
+            }
+            let (q, r) = n.div_rem_with(&d, &mut ctx).unwrap();
+            let (expected_q, expected_r) = n.div_rem(&d).unwrap();
+            assert_eq!((q, r), (expected_q, expected_r), "{:?} / {:?}", n, d);
+        }
+    }
+
+    #[test]
+    fn div_context_inline_array() {
+        let mut rng = SmallRng::seed_from_u64(4);
+        let mut ctx = DivContext::<InlineWordArray<4>>::new();
+        for _ in 0..1000 {
+            let (n, d) = (random(&mut rng, 4), random(&mut rng, 2));
+            let (q, r) = n.div_rem(&d).unwrap();
+            let (inline_q, inline_r) = n.convert::<InlineWordArray<4>>().unwrap()
+                .div_rem_with(&d.convert().unwrap(), &mut ctx).unwrap();
+            assert_eq!(inline_q.words(), q.words());
+            assert_eq!(inline_r.words(), r.words());
+        }
+    }
+
+    #[test]
+    fn pow_mod_small() {
+        let pow_mod = |base: u64, exp: u64, modulus: u64| {
+            let base: UnsignedInteger = base.into();
+            base.pow_mod(&exp.into(), &modulus.into()).unwrap()
+        };
+        assert_eq!(pow_mod(4, 13, 497), UnsignedInteger::from(445u64));
+        assert_eq!(pow_mod(4, 13, 498), UnsignedInteger::from(4u64.pow(13) % 498));
+        assert_eq!(pow_mod(7, 0, 1), UnsignedInteger::ZERO);
+        assert_eq!(pow_mod(7, 0, 10), UnsignedInteger::from(1u64));
+        assert!(matches!(
+            UnsignedInteger::<Vec<Word>>::ZERO.pow_mod(&1u64.into(), &UnsignedInteger::ZERO),
+            Err(PowError::ZeroModulus)
+        ));
+    }
+
+    #[test]
+    fn pow_mod_multi_word() {
+        let mut rng = SmallRng::seed_from_u64(269);
+        // Fermat's little theorem, modulo the Mersenne prime `2**127 - 1`
+        let prime = from_words(&[u64::MAX, u64::MAX >> 1]);
+        let exponent = from_words(&[u64::MAX - 1, u64::MAX >> 1]);
+        for _ in 0..10 {
+            let base = random(&mut rng, 3);
+            assert_eq!(base.pow_mod(&exponent, &prime).unwrap(), UnsignedInteger::from(1u64));
+        }
+        // Even moduli take the generic path, which should agree with repeated multiplication
+        for _ in 0..10 {
+            let (base, mut modulus) = (random(&mut rng, 3), random(&mut rng, 2));
+            modulus.set_bit(0, false).unwrap();
+            let mut expected = UnsignedInteger::from(1u64);
+            for _ in 0..5 {
+                expected = expected.mul_mod(&base, &modulus).unwrap();
+            }
+            assert_eq!(base.pow_mod(&5u64.into(), &modulus).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn pow_mod_full_inline_array() {
+        // The prime `2**255 - 19` fills an `InlineWordArray<4>`
+        let prime = from_words(&[u64::MAX - 18, u64::MAX, u64::MAX, u64::MAX >> 1]);
+        let exponent = from_words(&[u64::MAX - 19, u64::MAX, u64::MAX, u64::MAX >> 1]);
+        let mut rng = SmallRng::seed_from_u64(255);
+        let inline_prime: UnsignedInteger<InlineWordArray<4>> = prime.convert().unwrap();
+        let inline_exponent = exponent.convert().unwrap();
+        for _ in 0..5 {
+            let base = random(&mut rng, 4).convert::<InlineWordArray<4>>().unwrap();
+            let res = base.pow_mod(&inline_exponent, &inline_prime).unwrap();
+            assert_eq!(res.words(), [Word(1)]);
+        }
+    }
+
+    #[test]
+    fn checked_add_leaves_inputs_unchanged() {
+        let (a, b) = (from_words(&[u64::MAX, u64::MAX]), from_words(&[1]));
+        let sum = a.checked_add(&b).unwrap();
+        assert_eq!(sum, from_words(&[0, 0, 1]));
+        assert_eq!(a, from_words(&[u64::MAX, u64::MAX]));
+        assert_eq!(b, from_words(&[1]));
+        let mut total = a.clone();
+        total += b;
+        assert_eq!(total, sum);
+    }
+
+    #[test]
+    fn assign_operators_by_reference() {
+        let mut value = from_words(&[u64::MAX]);
+        value += &from_words(&[1]);
+        assert_eq!(value, from_words(&[0, 1]));
+        value -= &from_words(&[1]);
+        assert_eq!(value, from_words(&[u64::MAX]));
+        value *= &from_words(&[u64::MAX]);
+        assert_eq!(value, from_words(&[1, u64::MAX - 1]));
+        value -= &value.clone();
+        assert_eq!(value, UnsignedInteger::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "Subtraction underflowed")]
+    fn sub_assign_underflow() {
+        let mut value = from_words(&[1]);
+        value -= &from_words(&[2]);
+    }
+
+    #[test]
+    fn is_coprime() {
+        let coprime = |a: u64, b: u64| UnsignedInteger::<Vec<Word>>::from(a).is_coprime(&b.into()).unwrap();
+        assert!(!coprime(4, 6));
+        assert!(!coprime(0, 0));
+        assert!(!coprime(6, 9));
+        assert!(!coprime(10, 15));
+        assert!(coprime(4, 9));
+        assert!(coprime(8, 15));
+        assert!(coprime(0, 1));
+        assert!(!coprime(0, 7));
+        let (a, b) = (from_words(&[3, 1]), from_words(&[1, 0, 1]));
+        assert_eq!(a.is_coprime(&b).unwrap(), a.gcd(&b).unwrap().eq_u64(1));
+    }
+
+    #[test]
+    fn remove_factor() {
+        let mut value = small(2u64.pow(5) * 9 * 7);
+        assert_eq!(value.remove_factor(&4u64.into()).unwrap(), 2);
+        assert_eq!(value, small(2 * 9 * 7));
+        assert_eq!(value.remove_factor(&2u64.into()).unwrap(), 1);
+        assert_eq!(value.remove_factor(&3u64.into()).unwrap(), 2);
+        assert_eq!(value.remove_factor(&5u64.into()).unwrap(), 0);
+        assert_eq!(value, small(7));
+        // Zero and one are never removed, and zero is left alone
+        assert_eq!(value.remove_factor(&UnsignedInteger::ZERO).unwrap(), 0);
+        assert_eq!(value.remove_factor(&1u64.into()).unwrap(), 0);
+        assert_eq!(value, small(7));
+        let mut zero = UnsignedInteger::<Vec<Word>>::ZERO;
+        assert_eq!(zero.remove_factor(&3u64.into()).unwrap(), 0);
+        assert_eq!(zero, UnsignedInteger::ZERO);
+        // A multi-word factor
+        let prime = from_words(&[u64::MAX, u64::MAX >> 1]);
+        let cofactor = from_words(&[12345, 1]);
+        let mut value = UnsignedInteger::mul(&prime.pow(7).unwrap(), &cofactor).unwrap();
+        assert_eq!(value.remove_factor(&prime).unwrap(), 7);
+        assert_eq!(value, cofactor);
+        // A single word factor of a multi-word value, leaving a remainder at the end
+        let mut value = UnsignedInteger::mul(&small(3).pow(100).unwrap(), &small(7)).unwrap();
+        assert_eq!(value.remove_factor(&small(3)).unwrap(), 100);
+        assert_eq!(value, small(7));
+        // A full inline array, which only has room for the two buffers it swaps between
+        let mut inline = UnsignedInteger::<InlineWordArray<2>>::try_from_u128(3u128.pow(80)).unwrap();
+        assert_eq!(inline.remove_factor(&UnsignedInteger::try_from_u128(9).unwrap()).unwrap(), 40);
+        assert!(inline.is_one());
+        // `2**128 - 1 = (2**64 - 1) * (2**64 + 1)`
+        let mut inline = UnsignedInteger::<InlineWordArray<2>>::try_from_u128(u128::MAX).unwrap();
+        assert_eq!(inline.remove_factor(&UnsignedInteger::try_from_u128(u64::MAX as u128 + 2).unwrap()).unwrap(), 1);
+        assert_eq!(inline.words(), &[Word(u64::MAX)]);
+        let mut inline = UnsignedInteger::<InlineWordArray<2>>::try_from_u128(u128::MAX).unwrap();
+        let factor = UnsignedInteger::try_from_u128(u64::MAX as u128 + 4).unwrap();
+        assert_eq!(inline.remove_factor(&factor).unwrap(), 0);
+        assert_eq!(inline.words(), &[Word(u64::MAX); 2]);
+    }
+
+    #[test]
+    fn modpow_limited() {
+        let (base, modulus) = (small(4), small(497));
+        let exponent = small(1000);
+        assert_eq!(base.modpow_limited(&exponent, &modulus, 10).unwrap(), base.pow_mod(&exponent, &modulus).unwrap());
+        assert!(matches!(
+            base.modpow_limited(&exponent, &modulus, 9),
+            Err(PowError::ExponentTooLarge { bits: 10, max_squarings: 9 })
+        ));
+        assert_eq!(base.modpow_limited(&UnsignedInteger::ZERO, &modulus, 0).unwrap(), small(1));
+        // The limit is checked before the modulus
+        let huge = UnsignedInteger::ZERO.complement_to_bits(1 << 20).unwrap();
+        assert!(matches!(
+            base.modpow_limited(&huge, &UnsignedInteger::ZERO, 64),
+            Err(PowError::ExponentTooLarge { bits: 1_048_576, .. })
+        ));
+    }
+
+    #[test]
+    fn div_rem_small_quotient() {
+        let mut rng = SmallRng::seed_from_u64(218);
+        for _ in 0..200 {
+            let d_len = rng.gen_range(1..6);
+            let d = random(&mut rng, d_len);
+            if d.is_zero() {
+                continue;
+            }
+            for quotient_bits in 0..70 {
+                let mut n = random(&mut rng, d_len);
+                UnsignedInteger::shl_assign(&mut n, quotient_bits).unwrap();
+                assert_eq!(n.div_rem_small_quotient(&d).unwrap(), n.div_rem(&d).unwrap());
+                check_div_rem(&n, &d);
+            }
+        }
+        assert!(matches!(small(5).div_rem_small_quotient(&UnsignedInteger::ZERO), Err(DivError::DivisionByZero)));
+        assert_eq!(small(5).div_rem_small_quotient(&small(7)).unwrap(), (UnsignedInteger::ZERO, small(5)));
+    }
+
+    #[test]
+    fn gcd_lehmer_matches_binary() {
+        let mut rng = SmallRng::seed_from_u64(224);
+        for _ in 0..300 {
+            let (a_len, b_len) = (rng.gen_range(1..24), rng.gen_range(1..24));
+            let (a, b) = (random(&mut rng, a_len), random(&mut rng, b_len));
+            assert_eq!(a.gcd(&b).unwrap(), a.gcd_binary(&b).unwrap(), "gcd({:?}, {:?})", a, b);
+            // A large common factor, so the result isn't just one
+            let common_len = rng.gen_range(1..8);
+            let common = random(&mut rng, common_len);
+            let (a, b) = (UnsignedInteger::mul(&a, &common).unwrap(), UnsignedInteger::mul(&b, &common).unwrap());
+            let expected = a.gcd_binary(&b).unwrap();
+            assert_eq!(a.gcd_lehmer(&b).unwrap(), expected);
+            assert_eq!(b.gcd_lehmer(&a).unwrap(), expected);
+        }
+        let a = random(&mut rng, 10);
+        assert_eq!(a.gcd_lehmer(&a).unwrap(), a);
+        assert_eq!(a.gcd_lehmer(&UnsignedInteger::ZERO).unwrap(), a);
+        // Consecutive Fibonacci numbers take the most Euclidean steps
+        let (mut fib_a, mut fib_b) = (small(1), small(1));
+        for _ in 0..2000 {
+            let next = fib_a.checked_add(&fib_b).unwrap();
+            fib_a = core::mem::replace(&mut fib_b, next);
+        }
+        assert_eq!(fib_b.gcd_lehmer(&fib_a).unwrap(), small(1));
+    }
+
+    #[test]
+    fn sort_equal_lengths() {
+        let mut rng = SmallRng::seed_from_u64(232);
+        // Equal top words, so most comparisons fall through to the low words
+        let mut values: Vec<UnsignedInteger> = (0..500)
+            .map(|_| from_words(&[rng.gen(), rng.gen_range(0..4), 7]))
+            .collect();
+        sort_unsigned(&mut values);
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert!(values.iter().all(|value| value.len() == 3));
+        // Differing only in the top word
+        let mut values = vec![from_words(&[5, 3]), from_words(&[5, 1]), from_words(&[5, 2])];
+        sort_by_magnitude(&mut values);
+        assert_eq!(values, vec![from_words(&[5, 1]), from_words(&[5, 2]), from_words(&[5, 3])]);
+    }
+    #[test]
+    fn sort_signed_by_magnitude() {
+        use crate::int::{SignedInteger, Sign};
+        let signed = |sign, words: &[u64]| SignedInteger::from_sign_magnitude(sign, from_words(words));
+        let mut values = vec![
+            signed(Sign::Negative, &[9, 1]),
+            signed(Sign::Positive, &[3]),
+            signed(Sign::Negative, &[2, 1]),
+            SignedInteger::ZERO,
+            signed(Sign::Negative, &[4]),
+        ];
+        sort_by_magnitude(&mut values);
+        let magnitudes: Vec<_> = values.iter().map(|value| value.magnitude().clone()).collect();
+        assert_eq!(magnitudes, vec![UnsignedInteger::ZERO, small(3), small(4), from_words(&[2, 1]), from_words(&[9, 1])]);
+    }
+
+    #[test]
+    fn heterogeneous_sub() {
+        let mut accumulator = from_words(&[0, 0, 1]);
+        let inline: UnsignedInteger<InlineWordArray<2>> = from_words(&[1, 1]).convert().unwrap();
+        accumulator.add_heterogeneous(&inline).unwrap();
+        UnsignedInteger::sub(&mut accumulator, &inline).unwrap();
+        assert_eq!(accumulator, from_words(&[0, 0, 1]));
+        assert_eq!(accumulator.checked_sub(&inline).unwrap(), Some(from_words(&[u64::MAX, u64::MAX - 1])));
+        assert_eq!(inline.cmp_magnitude(&accumulator), Ordering::Less);
+        // Underflow leaves the integer unchanged
+        let mut small_value = small(5);
+        assert!(matches!(UnsignedInteger::sub(&mut small_value, &inline), Err(SubError::Underflow)));
+        assert_eq!(small_value, small(5));
+        assert_eq!(small_value.checked_sub(&inline).unwrap(), None);
+    }
+
+    #[test]
+    fn limbs_round_trip() {
+        let value = from_words(&[1, 2, 3]);
+        assert_eq!(value.export_limbs_le(), vec![1, 2, 3]);
+        assert_eq!(value.export_limbs_be(), vec![3, 2, 1]);
+        assert_eq!(UnsignedInteger::import_limbs_le(&[1, 2, 3]).unwrap(), value);
+        assert_eq!(UnsignedInteger::import_limbs_be(&[3, 2, 1]).unwrap(), value);
+        // Leading zero limbs are dropped in either order
+        assert_eq!(UnsignedInteger::import_limbs_be(&[0, 0, 3, 2, 1]).unwrap(), value);
+        assert_eq!(UnsignedInteger::import_limbs(&[1, 2, 3, 0], Endian::Little).unwrap(), value);
+        let zero: UnsignedInteger = UnsignedInteger::import_limbs_le(&[0, 0]).unwrap();
+        assert!(zero.is_empty() && zero.export_limbs(Endian::Big).is_empty());
+        let inline: UnsignedInteger<InlineWordArray<2>> = UnsignedInteger::import_limbs_be(&[0, 7, 9]).unwrap();
+        assert_eq!(inline.words(), &[Word(9), Word(7)]);
+    }
+    #[test]
+    fn carry_chain_matches_unchecked_add() {
+        let mut rng = SmallRng::seed_from_u64(306);
+        let random = |rng: &mut SmallRng| {
+            let len = rng.gen_range(0..=8);
+            // Mostly all-ones words, so carries run through long stretches
+            UnsignedInteger::from_words_iter((0..len).map(|_| Word(if rng.gen_bool(0.7) { u64::MAX } else { rng.gen() })))
+                .unwrap()
+        };
+        for _ in 0..2000 {
+            let (left, right): (UnsignedInteger, UnsignedInteger) = (random(&mut rng), random(&mut rng));
+            let extra = left.len().max(right.len()) + 1 - left.len();
+            let mut expected = left.clone_with_capacity(extra).unwrap();
+            let mut actual = left.clone_with_capacity(extra).unwrap();
+            unsafe {
+                expected.unchecked_add(&right);
+                actual.unchecked_add_carry_chain(&right);
+            }
+            assert_eq!(actual, expected, "{:?} + {:?}", left, right);
+        }
+    }
+    /// A mock array claiming to be about half the address space,
+    /// so the product of two of them has a length overflowing a [usize]
+    ///
+    /// Only the reported length is huge. Reserving or touching the words panics.
+    #[derive(Clone, Debug, Default)]
+    struct HugeWordArray {
+        len: usize
+    }
+    impl AsRef<[Word]> for HugeWordArray {
+        fn as_ref(&self) -> &[Word] {
+            panic!("Read the words of a huge array")
+        }
+    }
+    impl AsMut<[Word]> for HugeWordArray {
+        fn as_mut(&mut self) -> &mut [Word] {
+            panic!("Wrote the words of a huge array")
+        }
+    }
+    unsafe impl WordArray for HugeWordArray {
+        const EMPTY: Self = HugeWordArray { len: 0 };
+        type AllocErr = CapacityExceeded;
+        fn with_capacity(capacity: usize) -> Result<Self, CapacityExceeded> {
+            assert_eq!(capacity, 0, "Allocated a huge array");
+            Ok(Self::EMPTY)
+        }
+        fn len(&self) -> usize {
+            self.len
+        }
+        fn clear(&mut self) {
+            self.len = 0;
+        }
+        fn truncate(&mut self, len: usize) {
+            self.len = self.len.min(len);
+        }
+        fn capacity(&self) -> usize {
+            self.len
+        }
+        fn reserve(&mut self, additional: usize) -> Result<(), CapacityExceeded> {
+            panic!("Reserved {} words despite the overflow", additional)
+        }
+        unsafe fn unchecked_push(&mut self, _word: Word) {
+            panic!("Pushed onto a huge array")
+        }
+    }
+    #[test]
+    fn mul_length_overflow() {
+        let huge = UnsignedInteger { words: HugeWordArray { len: usize::MAX / 2 + 1 } };
+        let mut out = UnsignedInteger { words: HugeWordArray::EMPTY };
+        let err = huge.mul_into(&huge, &mut out).unwrap_err();
+        assert!(err.is_capacity_overflow());
+        assert_eq!(err, CapacityExceeded::capacity_arithmetic_overflow());
+        assert!(UnsignedInteger::mul(&huge, &huge).unwrap_err().is_capacity_overflow());
+    }
+    #[test]
+    fn inline_shl_past_capacity() {
+        let mut value: UnsignedInteger<InlineWordArray<4>> = UnsignedInteger::from_words_iter(
+            [Word(u64::MAX), Word(2), Word(3), Word(1)].iter().copied()
+        ).unwrap();
+        let original = value.clone();
+        // Shifting by 70 bits moves up a whole word, needing a fifth one
+        assert_eq!(UnsignedInteger::shl_assign(&mut value, 70), Err(CapacityExceeded { required: 5 }));
+        assert_eq!(value, original);
+        // The top bit of the top word still has room for 6 bits
+        UnsignedInteger::shl_assign(&mut value, 6).unwrap();
+        assert_eq!(value.bit_len(), 199);
+    }
+    #[test]
+    fn reduce_once() {
+        let modulus = small(7);
+        let mut value = small(12);
+        assert!(value.reduce_once(&modulus));
+        assert_eq!(value, small(5));
+        assert!(!value.reduce_once(&modulus));
+        assert_eq!(value, small(5));
+        let mut value = small(7);
+        assert!(value.reduce_once(&modulus));
+        assert!(value.is_empty());
+    }
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Reducing modulo zero")]
+    fn reduce_once_by_zero() {
+        small(5).reduce_once(&UnsignedInteger::ZERO);
+    }
+    #[test]
+    fn add_assign_nogrow() {
+        let inline = |words: &[u64]| -> UnsignedInteger<InlineWordArray<2>> {
+            UnsignedInteger::from_words_iter(words.iter().map(|&word| Word(word))).unwrap()
+        };
+        // Exactly full: the carry stays within the two words
+        let mut value = inline(&[u64::MAX, 1]);
+        value.add_assign_nogrow(&inline(&[1])).unwrap();
+        assert_eq!(value, inline(&[0, 2]));
+        // One word short: the carry needs a third word
+        let mut value = inline(&[u64::MAX, u64::MAX]);
+        assert_eq!(value.add_assign_nogrow(&inline(&[1])), Err(CapacityExceeded { required: 3 }));
+        assert_eq!(value, inline(&[u64::MAX, u64::MAX]));
+        // Growing into spare capacity is fine, since it never reserves
+        let mut value = inline(&[u64::MAX]);
+        value.add_assign_nogrow(&inline(&[1])).unwrap();
+        assert_eq!(value, inline(&[0, 1]));
+    }
+    #[test]
+    fn is_one_and_set_one() {
+        let mut value = from_words(&[5, 6, 7]);
+        assert!(!value.is_one());
+        UnsignedInteger::set_one(&mut value).unwrap();
+        assert!(value.is_one());
+        assert_eq!(value.words(), &[Word(1)]);
+        assert!(!small(0).is_one());
+        assert!(!small(2).is_one());
+        // The low word alone being one isn't enough
+        assert!(!from_words(&[1, 1]).is_one());
+        let mut inline = UnsignedInteger::<InlineWordArray<0>>::ZERO;
+        assert_eq!(UnsignedInteger::set_one(&mut inline), Err(CapacityExceeded { required: 1 }));
+    }
+    #[test]
+    fn sub_borrows_and_normalizes() {
+        // The borrow runs all the way up, emptying the top word
+        let mut value = from_words(&[0, 0, 1]);
+        UnsignedInteger::sub(&mut value, &small(1)).unwrap();
+        assert_eq!(value.words(), &[Word(u64::MAX), Word(u64::MAX)]);
+        assert_eq!(value.check_invariants(), Ok(()));
+        // Equal values leave the empty array of zero
+        UnsignedInteger::sub(&mut value, &from_words(&[u64::MAX, u64::MAX])).unwrap();
+        assert!(value.is_empty());
+        assert!(matches!(UnsignedInteger::sub(&mut value, &small(1)), Err(SubError::Underflow)));
+        assert_eq!(small(0).checked_sub(&small(0)).unwrap(), Some(small(0)));
+        let mut rng = SmallRng::seed_from_u64(251);
+        for _ in 0..500 {
+            let (left_len, right_len) = (rng.gen_range(0..6), rng.gen_range(0..6));
+            let (left, right) = (random(&mut rng, left_len), random(&mut rng, right_len));
+            let sum = left.checked_add(&right).unwrap();
+            assert_eq!(sum.checked_sub(&right).unwrap(), Some(left.clone()));
+            let mut difference = sum.clone();
+            difference.unchecked_sub(&left);
+            assert_eq!(difference, right);
+            assert_eq!(difference.check_invariants(), Ok(()));
+        }
+    }
+    #[test]
+    fn mul_products() {
+        let parse = |s: &str| s.parse::<UnsignedInteger>().unwrap();
+        // A single word squared overflows into two
+        let square = UnsignedInteger::mul(&small(u64::MAX), &small(u64::MAX)).unwrap();
+        assert_eq!(square.words(), &[Word(1), Word(u64::MAX - 1)]);
+        assert_eq!(square, parse("340282366920938463426481119284349108225"));
+        let product = UnsignedInteger::mul(
+            &parse("123456789012345678901234567890"),
+            &parse("987654321098765432109876543210")
+        ).unwrap();
+        assert_eq!(product, parse("121932631137021795226185032733622923332237463801111263526900"));
+        assert_eq!(product.check_invariants(), Ok(()));
+        // Multiplying by zero gives the empty array
+        assert!(UnsignedInteger::mul(&product, &small(0)).unwrap().is_empty());
+        assert!(UnsignedInteger::mul(&small(0), &product).unwrap().is_empty());
+        let mut assigned = parse("123456789012345678901234567890");
+        assigned *= &parse("987654321098765432109876543210");
+        assert_eq!(assigned, product);
+    }
+    #[test]
+    fn mul_u64() {
+        let mut value = from_words(&[u64::MAX, 3]);
+        value.mul_u64(1).unwrap();
+        assert_eq!(value, from_words(&[u64::MAX, 3]));
+        // The final carry gets its own word
+        value.mul_u64(u64::MAX).unwrap();
+        assert_eq!(value, UnsignedInteger::mul(&from_words(&[u64::MAX, 3]), &small(u64::MAX)).unwrap());
+        assert_eq!(value.len(), 3);
+        value.mul_u64(0).unwrap();
+        assert!(value.is_empty());
+        let mut value = from_words(&[1 << 63, 1 << 63]).clone_with_capacity(1).unwrap();
+        unsafe { value.unchecked_mul_u64(2) };
+        assert_eq!(value, from_words(&[0, 1, 1]));
+        unsafe { value.unchecked_mul_u64(0) };
+        assert!(value.is_empty());
+    }
+    #[test]
+    fn ordering() {
+        assert_eq!(UnsignedInteger::<Vec<Word>>::ZERO, UnsignedInteger::ZERO);
+        assert_eq!(small(0).cmp(&small(0)), Ordering::Equal);
+        // Equal lengths, differing in a lower word
+        assert!(from_words(&[1, 5]) < from_words(&[2, 5]));
+        assert!(from_words(&[u64::MAX, 5]) > from_words(&[0, 5]));
+        // Differing only in the top word, which decides even against larger lower words
+        assert!(from_words(&[7, 7, 1]) < from_words(&[7, 7, 2]));
+        assert!(from_words(&[0, 0, 2]) > from_words(&[u64::MAX, u64::MAX, 1]));
+        // A longer normalized integer is always larger
+        assert!(from_words(&[0, 1]) > small(u64::MAX));
+        assert!(small(0) < small(1));
+        let mut values = vec![from_words(&[0, 0, 2]), small(3), from_words(&[5, 1]), small(0), from_words(&[4, 1])];
+        values.sort();
+        assert_eq!(values, vec![small(0), small(3), from_words(&[4, 1]), from_words(&[5, 1]), from_words(&[0, 0, 2])]);
+        assert_eq!(values.iter().max(), Some(&from_words(&[0, 0, 2])));
+    }
+    #[test]
+    fn one_trait() {
+        let one: UnsignedInteger = One::one();
+        assert_eq!(one, "1".parse().unwrap());
+        assert!(One::is_one(&one));
+        let mut value = from_words(&[0, 9]);
+        One::set_one(&mut value);
+        assert_eq!(value, one);
+        let inline: UnsignedInteger<InlineWordArray<1>> = UnsignedInteger::one().unwrap();
+        assert_eq!(inline.words(), &[Word(1)]);
+    }
+    #[test]
+    fn shifts_cross_words() {
+        // A one-word value shifted by 70 bits lands in the second word
+        assert_eq!(small(3) << 70, from_words(&[0, 3 << 6]));
+        assert_eq!(from_words(&[0, 3 << 6]) >> 70, small(3));
+        // Bits carry out of the top word into a new one
+        assert_eq!(small(u64::MAX) << 4, from_words(&[u64::MAX << 4, 0xf]));
+        assert_eq!(from_words(&[0, 0, 1]) >> 1, from_words(&[0, 1 << 63]));
+        assert_eq!(small(0) << 1000, small(0));
+        assert_eq!(from_words(&[1, 2, 3]) >> 192, small(0));
+        assert_eq!(from_words(&[1, 2, 3]) >> 1000, small(0));
+        let mut rng = SmallRng::seed_from_u64(263);
+        for _ in 0..500 {
+            let value = rng.gen::<u128>() >> rng.gen_range(0..128);
+            let bits = rng.gen_range(0..128);
+            let big: UnsignedInteger = value.into();
+            assert_eq!(big.clone() >> bits, (value >> bits).into());
+            if bits <= value.leading_zeros() {
+                assert_eq!(big.clone() << bits, (value << bits).into());
+            }
+            // Shifting left and back is lossless
+            let shifted = big.clone() << bits;
+            assert_eq!(shifted.bit_len(), if value == 0 { 0 } else { big.bit_len() + bits as u64 });
+            assert_eq!(shifted >> bits, big);
+        }
+    }
+    #[test]
+    fn bit_len_and_counts() {
+        assert_eq!(small(0).bit_len(), 0);
+        assert_eq!(small(0).count_ones(), 0);
+        assert_eq!(small(0).trailing_zeros(), None);
+        // Top words with leading zeros
+        assert_eq!(small(1).bit_len(), 1);
+        assert_eq!(from_words(&[u64::MAX, 1]).bit_len(), 65);
+        assert_eq!(from_words(&[0, 0, 0x10]).bit_len(), 133);
+        assert_eq!(from_words(&[0, 0, 1 << 62]).bit_len(), 191);
+        assert_eq!(from_words(&[0, u64::MAX]).bit_len(), 128);
+        assert_eq!(from_words(&[u64::MAX, 0b1011]).count_ones(), 67);
+        assert_eq!(from_words(&[0, 0, 0x10]).trailing_zeros(), Some(132));
+        assert_eq!(small(1).trailing_zeros(), Some(0));
+    }
+    #[test]
+    fn from_primitives() {
+        assert!(UnsignedInteger::<Vec<Word>>::from(0u64).is_empty());
+        assert!(UnsignedInteger::<Vec<Word>>::from(0u128).is_empty());
+        assert!(UnsignedInteger::<Vec<Word>>::from(0u8).is_empty());
+        assert_eq!(UnsignedInteger::<Vec<Word>>::from(u64::MAX).words(), &[Word(u64::MAX)]);
+        assert_eq!(UnsignedInteger::<Vec<Word>>::from(u128::MAX).words(), &[Word(u64::MAX); 2]);
+        assert_eq!(UnsignedInteger::<Vec<Word>>::from(u64::MAX as u128).words(), &[Word(u64::MAX)]);
+        assert_eq!(UnsignedInteger::<Vec<Word>>::from(7u16), UnsignedInteger::from(7usize));
+        let inline = UnsignedInteger::<InlineWordArray<2>>::try_from_u128(u128::MAX).unwrap();
+        assert_eq!(inline.words(), &[Word(u64::MAX); 2]);
+        assert!(UnsignedInteger::<InlineWordArray<2>>::try_from_u128(0).unwrap().is_empty());
+        assert_eq!(
+            UnsignedInteger::<InlineWordArray<1>>::try_from_u128(u128::MAX),
+            Err(CapacityExceeded { required: 2 })
+        );
+    }
+    #[test]
+    fn to_primitives() {
+        let max = small(u64::MAX);
+        assert_eq!(u64::try_from(&max), Ok(u64::MAX));
+        assert_eq!(max.to_u128(), Some(u64::MAX as u128));
+        let past_word: UnsignedInteger = (u64::MAX as u128 + 1).into();
+        assert_eq!(u64::try_from(&past_word), Err(TooLarge));
+        assert_eq!(past_word.to_u128(), Some(u64::MAX as u128 + 1));
+        let max_u128: UnsignedInteger = u128::MAX.into();
+        assert_eq!(u64::try_from(&max_u128), Err(TooLarge));
+        assert_eq!(max_u128.to_u128(), Some(u128::MAX));
+        // One word over
+        let three_words = from_words(&[0, 0, 1]);
+        assert_eq!(three_words.to_u128(), None);
+        assert_eq!(u64::try_from(&three_words), Err(TooLarge));
+        assert_eq!(u64::try_from(&small(0)), Ok(0));
+        assert_eq!(small(0).to_u128(), Some(0));
+    }
+    #[test]
+    fn mul_mod_matches_naive() {
+        for a in 0..20u64 {
+            for b in 0..20u64 {
+                for m in 1..20u64 {
+                    assert_eq!(small(a).mul_mod(&small(b), &small(m)).unwrap(), small(a * b % m), "{} * {} % {}", a, b, m);
+                }
+            }
+        }
+        let mut rng = SmallRng::seed_from_u64(270);
+        for _ in 0..200 {
+            let (a, b, m) = (rng.gen::<u64>(), rng.gen::<u64>(), rng.gen_range(1..=u64::MAX));
+            let expected = (a as u128 * b as u128 % m as u128) as u64;
+            let res = small(a).mul_mod(&small(b), &small(m)).unwrap();
+            assert_eq!(res, small(expected));
+            assert!(res < small(m));
+        }
+        assert!(matches!(small(3).mul_mod(&small(4), &small(0)), Err(DivError::DivisionByZero)));
+    }
+    #[test]
+    fn gcd_cases() {
+        assert_eq!(small(0).gcd(&small(0)).unwrap(), small(0));
+        assert_eq!(small(0).gcd(&small(12)).unwrap(), small(12));
+        assert_eq!(small(12).gcd(&small(0)).unwrap(), small(12));
+        // Coprime inputs
+        assert_eq!(small(35).gcd(&small(64)).unwrap(), small(1));
+        assert_eq!(small(u64::MAX).gcd(&small(u64::MAX - 1)).unwrap(), small(1));
+        // One dividing the other
+        let divisor = from_words(&[7, 3]);
+        let multiple = UnsignedInteger::mul(&divisor, &from_words(&[11, 13])).unwrap();
+        assert_eq!(multiple.gcd(&divisor).unwrap(), divisor);
+        assert_eq!(divisor.gcd(&multiple).unwrap(), divisor);
+        // Consecutive Fibonacci numbers are coprime, and the worst case for Euclid
+        let (mut a, mut b) = (small(0), small(1));
+        for _ in 0..300 {
+            let next = a.checked_add(&b).unwrap();
+            a = core::mem::replace(&mut b, next);
+        }
+        assert!(b.bit_len() > 200);
+        assert_eq!(a.gcd(&b).unwrap(), small(1));
+        assert_eq!(b.gcd(&a).unwrap(), small(1));
+    }
+    #[test]
+    fn bitwise_identities() {
+        let mut rng = SmallRng::seed_from_u64(272);
+        for len in 0..6 {
+            let a = random(&mut rng, len);
+            let b = random(&mut rng, len + 1);
+            assert!((a.clone() ^ a.clone()).is_empty());
+            assert!((a.clone() & UnsignedInteger::ZERO).is_empty());
+            assert_eq!(a.clone() | UnsignedInteger::ZERO, a);
+            assert_eq!(a.clone() ^ UnsignedInteger::ZERO, a);
+            // XOR can clear the top words, which must be trimmed
+            let c = (a.clone() ^ b.clone()) ^ b.clone();
+            assert_eq!(c, a);
+            assert_eq!(c.check_invariants(), Ok(()));
+            let and = a.clone() & b.clone();
+            assert_eq!(and.check_invariants(), Ok(()));
+        }
+        assert_eq!(small(0b1010).complement_to_bits(6).unwrap(), small(0b110101));
+        assert_eq!(small(0).complement_to_bits(70).unwrap(), from_words(&[u64::MAX, 0x3f]));
+    }
+    #[test]
+    fn parity() {
+        assert!(small(0).is_even() && !small(0).is_odd());
+        assert!(small(1).is_odd() && !small(1).is_even());
+        assert!(small(2).is_even() && !small(2).is_odd());
+        assert!(small(u64::MAX).is_odd() && !small(u64::MAX).is_even());
+        assert!(from_words(&[2, 1]).is_even());
+    }
+    #[test]
+    fn add_into_reuses_out() {
+        let mut rng = SmallRng::seed_from_u64(274);
+        let mut out = UnsignedInteger::ZERO;
+        // Warm up with the largest possible sum
+        from_words(&[u64::MAX; 8]).add_into(&from_words(&[u64::MAX; 8]), &mut out).unwrap();
+        let (pointer, capacity) = (out.words.as_ptr(), out.words.capacity());
+        for _ in 0..100 {
+            let (left_len, right_len) = (rng.gen_range(0..=8), rng.gen_range(0..=8));
+            let (left, right) = (random(&mut rng, left_len), random(&mut rng, right_len));
+            left.add_into(&right, &mut out).unwrap();
+            assert_eq!(out, left.checked_add(&right).unwrap());
+            assert_eq!((out.words.as_ptr(), out.words.capacity()), (pointer, capacity));
+        }
+    }
+    #[test]
+    fn karatsuba_matches_schoolbook() {
+        let mut rng = SmallRng::seed_from_u64(275);
+        for &(left_len, right_len) in &[(100, 100), (100, 99), (100, 64), (100, 33), (150, 100)] {
+            let (left, right) = (random(&mut rng, left_len), random(&mut rng, right_len));
+            let (left_len, right_len) = (left.len(), right.len());
+            // Grade school multiplication, exactly as below the threshold
+            let mut expected = vec![Word(0); left_len + right_len];
+            for (offset, &multiplier) in right.words().iter().enumerate() {
+                let carry = unsafe { mul_add_word_assign(&mut expected[offset..], left.words(), multiplier) };
+                expected[offset + left_len] = carry;
+            }
+            assert!(right_len >= KARATSUBA_THRESHOLD);
+            let product = UnsignedInteger::mul(&left, &right).unwrap();
+            let mut expected = UnsignedInteger::from_word_array(expected);
+            expected.normalize();
+            assert_eq!(product, expected);
+        }
+    }
+    #[test]
+    fn mul_config_thresholds() {
+        let mut rng = SmallRng::seed_from_u64(275);
+        let schoolbook = MulConfig::new().with_karatsuba_threshold(usize::MAX);
+        // The smallest threshold recurses the deepest, including on unbalanced operands
+        let eager = MulConfig::new().with_karatsuba_threshold(0);
+        assert_eq!(eager.karatsuba_threshold(), 4);
+        assert_eq!(MulConfig::default(), MulConfig::DEFAULT);
+        for _ in 0..200 {
+            let (left_len, right_len) = (rng.gen_range(0..80), rng.gen_range(0..80));
+            let (left, right) = (random(&mut rng, left_len), random(&mut rng, right_len));
+            let expected = left.mul_with(&right, &schoolbook).unwrap();
+            assert_eq!(left.mul_with(&right, &eager).unwrap(), expected, "{} x {} words", left_len, right_len);
+            assert_eq!(UnsignedInteger::mul(&left, &right).unwrap(), expected);
+        }
+    }
+    #[test]
+    fn bytes_round_trip() {
+        let max = small(u64::MAX);
+        assert_eq!(max.to_le_bytes(), vec![0xff; 8]);
+        assert_eq!(UnsignedInteger::from_le_bytes(&max.to_le_bytes()).unwrap(), max);
+        assert_eq!(UnsignedInteger::from_be_bytes(&max.to_be_bytes()).unwrap(), max);
+        // A zero low word keeps its zero bytes, but not any past the top
+        let high = from_words(&[0, 0x0102]);
+        let mut expected = vec![0; 8];
+        expected.extend_from_slice(&[2, 1]);
+        assert_eq!(high.to_le_bytes(), expected);
+        expected.reverse();
+        assert_eq!(high.to_be_bytes(), expected);
+        assert_eq!(UnsignedInteger::from_le_bytes(&high.to_le_bytes()).unwrap(), high);
+        assert_eq!(UnsignedInteger::from_be_bytes(&high.to_be_bytes()).unwrap(), high);
+        // Zero is the empty slice
+        let zero: UnsignedInteger = UnsignedInteger::from_le_bytes(&[]).unwrap();
+        assert!(zero.is_empty());
+        assert!(zero.to_le_bytes().is_empty() && zero.to_be_bytes().is_empty());
+        assert!(UnsignedInteger::<Vec<Word>>::from_be_bytes(&[0, 0, 0]).unwrap().is_empty());
+    }
+    #[test]
+    fn normalize_padding() {
+        let mut value = UnsignedInteger::from_word_array(vec![Word(5), Word(0), Word(0)]);
+        assert!(value.check_invariants().is_err());
+        value.normalize();
+        assert_eq!(value.words().len(), 1);
+        assert_eq!(value, small(5));
+        let mut zero = UnsignedInteger::from_word_array(vec![Word(0)]);
+        zero.normalize();
+        assert!(zero.is_empty());
+    }
+    #[test]
+    fn try_from_word_array() {
+        assert_eq!(UnsignedInteger::try_from_word_array(vec![Word(5), Word(1)]), Ok(from_words(&[5, 1])));
+        assert_eq!(UnsignedInteger::try_from_word_array(Vec::new()), Ok(small(0)));
+        assert_eq!(UnsignedInteger::try_from_word_array(vec![Word(5), Word(0)]), Err(NotNormalized { len: 2 }));
+        assert_eq!(UnsignedInteger::try_from_word_array(vec![Word(0)]), Err(NotNormalized { len: 1 }));
+        let inline = InlineWordArray::<2>::from_array([Word(1), Word(0)], 2);
+        assert_eq!(UnsignedInteger::try_from_word_array(inline).unwrap_err(), NotNormalized { len: 2 });
+    }
+    #[test]
+    fn clone_and_debug() {
+        let value = from_words(&[0xabc, 0xdef]);
+        let cloned = value.clone();
+        assert_eq!(cloned, value);
+        let debug = format!("{:?}", value);
+        assert_eq!(debug, "UnsignedInteger(0xdef0000000000000abc)");
+        assert!(debug.contains(&format!("{:x}", value)));
+        assert_eq!(format!("{:?}", small(0)), "UnsignedInteger(0x0)");
+    }
+    #[test]
+    fn shrink_to_fit() {
+        let mut value = UnsignedInteger::from_word_array(Vec::with_capacity(1000));
+        value.set(7).unwrap();
+        assert!(value.as_word_array().capacity() >= 1000);
+        value.shrink_to_fit();
+        assert!(value.as_word_array().capacity() < 1000);
+        assert_eq!(value, small(7));
+        // Fixed arrays can't shrink, so it's a no-op
+        let mut inline = UnsignedInteger::<InlineWordArray<4>>::try_from_u128(7).unwrap();
+        inline.shrink_to_fit();
+        assert_eq!(inline.as_word_array().capacity(), 4);
+        assert_eq!(inline.words(), &[Word(7)]);
+    }
+    #[test]
+    fn from_words_iter_trims() {
+        let words = [3, 4, 0, 0].iter().map(|&word| Word(word));
+        let value: UnsignedInteger = UnsignedInteger::from_words_iter(words.clone()).unwrap();
+        assert_eq!(value.words(), &[Word(3), Word(4)]);
+        assert_eq!(value.check_invariants(), Ok(()));
+        // A lazy iterator without an exact size hint works too
+        let lazy = (1..=6u64).map(|word| Word(if word > 2 { 0 } else { word })).filter(|_| true);
+        assert_eq!(UnsignedInteger::<Vec<Word>>::from_words_iter(lazy).unwrap(), from_words(&[1, 2]));
+        let inline: UnsignedInteger<InlineWordArray<2>> = UnsignedInteger::from_words_iter(words.take(3)).unwrap();
+        assert_eq!(inline.words(), &[Word(3), Word(4)]);
+        let zeros = core::iter::repeat_n(Word(0), 5);
+        assert!(UnsignedInteger::<Vec<Word>>::from_words_iter(zeros).unwrap().is_empty());
+    }
+    #[test]
+    fn bounded_width_addition() {
+        let mut wrapped = small(250);
+        wrapped.wrapping_add_bits(&small(10), 8).unwrap();
+        assert_eq!(wrapped, small(4));
+        let mut saturated = small(250);
+        saturated.saturating_add_bits(&small(10), 8).unwrap();
+        assert_eq!(saturated, small(255));
+        let mut overflowing = small(250);
+        assert!(overflowing.overflowing_add_bits(&small(10), 8).unwrap());
+        assert_eq!(overflowing, small(4));
+        // Sums that still fit are left alone
+        let mut exact = small(250);
+        exact.saturating_add_bits(&small(5), 8).unwrap();
+        assert_eq!(exact, small(255));
+        exact.wrapping_add_bits(&small(1), 8).unwrap();
+        assert!(exact.is_empty());
+        let mut wide = small(u64::MAX);
+        wide.truncate_to_bits(8);
+        assert_eq!(wide, small(255));
+        let mut across = from_words(&[u64::MAX, u64::MAX]);
+        across.truncate_to_bits(64);
+        assert_eq!(across, small(u64::MAX));
+    }
+    #[test]
+    fn pow() {
+        assert_eq!(small(2).pow(64).unwrap(), from_words(&[0, 1]));
+        assert_eq!(small(2).pow(63).unwrap(), small(1 << 63));
+        assert_eq!(small(10).pow(30).unwrap(), "1000000000000000000000000000000".parse().unwrap());
+        assert!(small(0).pow(5).unwrap().is_empty());
+        assert_eq!(small(0).pow(0).unwrap(), small(1));
+        assert_eq!(small(12345).pow(0).unwrap(), small(1));
+        assert_eq!(small(3).pow(1).unwrap(), small(3));
+        assert_eq!(from_words(&[0, 1]).pow(3).unwrap(), from_words(&[0, 0, 0, 1]));
+    }
+    #[test]
+    fn isqrt() {
+        assert!(small(0).isqrt().unwrap().is_empty());
+        assert_eq!(small(1).isqrt().unwrap(), small(1));
+        for root in (2..2000u64).chain(Some(u32::MAX as u64)) {
+            let square = small(root * root);
+            assert_eq!(square.isqrt().unwrap(), small(root), "isqrt({})", root * root);
+            assert_eq!(small(root * root - 1).isqrt().unwrap(), small(root - 1), "isqrt({})", root * root - 1);
+        }
+        assert_eq!(small(u64::MAX).isqrt().unwrap(), small(u32::MAX as u64));
+        let mut rng = SmallRng::seed_from_u64(287);
+        let mut value = random(&mut rng, 5);
+        value.truncate_to_bits(300);
+        value.set_bit(299, true).unwrap();
+        let root = value.isqrt().unwrap();
+        assert_eq!(root.bit_len(), 150);
+        let mut next = root.clone();
+        next.add_u64(1).unwrap();
+        assert!(UnsignedInteger::mul(&root, &root).unwrap() <= value);
+        assert!(UnsignedInteger::mul(&next, &next).unwrap() > value);
+        // An exact 300-bit square, and one below it
+        let square = UnsignedInteger::mul(&root, &root).unwrap();
+        assert_eq!(square.isqrt().unwrap(), root);
+        let below = square.checked_sub(&small(1)).unwrap().unwrap();
+        assert_eq!(below.isqrt().unwrap(), root.checked_sub(&small(1)).unwrap().unwrap());
+    }
+    #[test]
+    fn get_and_set_bits() {
+        let mut value = small(0);
+        value.set_bit(130, true).unwrap();
+        assert_eq!(value.bit_len(), 131);
+        assert_eq!(value.words(), &[Word(0), Word(0), Word(1 << 2)]);
+        assert!(value.get_bit(130));
+        assert!(!value.get_bit(129) && !value.get_bit(131) && !value.get_bit(10_000));
+        value.set_bit(3, true).unwrap();
+        value.set_bit(130, false).unwrap();
+        assert_eq!(value, small(8));
+        assert_eq!(value.check_invariants(), Ok(()));
+        value.set_bit(3, false).unwrap();
+        assert_eq!(value, UnsignedInteger::ZERO);
+        // Clearing a bit past the top never grows
+        value.set_bit(500, false).unwrap();
+        assert!(value.is_empty());
+    }
+    #[test]
+    fn power_of_two_fast_paths() {
+        let mut rng = SmallRng::seed_from_u64(295);
+        for _ in 0..200 {
+            let len = rng.gen_range(0..5);
+            let x = random(&mut rng, len);
+            let k = rng.gen_range(0..300);
+            let mut power = small(0);
+            power.set_bit(k as u64, true).unwrap();
+            let shifted = x.clone() << k;
+            assert_eq!(UnsignedInteger::mul(&x, &power).unwrap(), shifted);
+            assert_eq!(UnsignedInteger::mul(&power, &x).unwrap(), shifted);
+            let (quotient, remainder) = x.div_rem(&power).unwrap();
+            assert_eq!(quotient, x.clone() >> k);
+            let mut low = x.clone();
+            low.truncate_to_bits(k as u64);
+            assert_eq!(remainder, low);
+        }
+    }
+    #[test]
+    fn compare_with_u64() {
+        assert!(small(0).eq_u64(0));
+        assert!(!small(0).eq_u64(1));
+        assert_eq!(small(0).cmp_u64(0), Ordering::Equal);
+        assert_eq!(small(0).cmp_u64(1), Ordering::Less);
+        let max = small(u64::MAX);
+        assert!(max.eq_u64(u64::MAX));
+        assert!(!max.eq_u64(u64::MAX - 1));
+        assert_eq!(max.cmp_u64(u64::MAX), Ordering::Equal);
+        assert_eq!(max.cmp_u64(u64::MAX - 1), Ordering::Greater);
+        assert_eq!(small(5).cmp_u64(u64::MAX), Ordering::Less);
+        // Two words exceed any u64, even with a zero low word
+        let two_words = from_words(&[0, 1]);
+        assert!(!two_words.eq_u64(0));
+        assert_eq!(two_words.cmp_u64(u64::MAX), Ordering::Greater);
+        assert_eq!(two_words.cmp_u64(0), Ordering::Greater);
+    }
+    #[test]
+    fn decimal_len() {
+        assert_eq!(small(0).decimal_len(), 1);
+        for &value in &[1, 9, 10, 99, 100, 999, 1000, 1001, 9999, 10_000, u64::MAX] {
+            assert_eq!(small(value).decimal_len(), value.to_string().len() as u64, "{}", value);
+        }
+        // Every power of ten up to 10^60, along with its neighbors
+        let mut power = small(1);
+        for exponent in 0..=60u64 {
+            assert_eq!(power.decimal_len(), exponent + 1);
+            let mut above = power.clone();
+            above.add_u64(1).unwrap();
+            assert_eq!(above.decimal_len(), if exponent == 0 { 1 } else { exponent + 1 });
+            if exponent > 0 {
+                let below = power.checked_sub(&small(1)).unwrap().unwrap();
+                assert_eq!(below.decimal_len(), exponent, "10^{} - 1", exponent);
+            }
+            power.mul_u64(10).unwrap();
+        }
+    }
+    #[test]
+    fn convert_between_backends() {
+        let value: UnsignedInteger = "340282366920938463463374607431768211455".parse().unwrap();
+        let inline: UnsignedInteger<InlineWordArray<8>> = value.convert().unwrap();
+        assert_eq!(inline.words(), value.words());
+        assert_eq!(inline.check_invariants(), Ok(()));
+        let exact: UnsignedInteger<InlineWordArray<2>> = value.convert().unwrap();
+        assert_eq!(exact.words(), value.words());
+        assert_eq!(value.convert::<InlineWordArray<1>>(), Err(CapacityExceeded { required: 2 }));
+        // And back to the heap
+        let back: UnsignedInteger = inline.convert().unwrap();
+        assert_eq!(back, value);
+        assert!(small(0).convert::<InlineWordArray<0>>().unwrap().is_empty());
+    }
+    #[test]
+    fn inline_add_past_capacity() {
+        let mut value = UnsignedInteger::<InlineWordArray<1>>::try_from_u128(u64::MAX as u128).unwrap();
+        let one = UnsignedInteger::<InlineWordArray<1>>::try_from_u128(1).unwrap();
+        assert_eq!(UnsignedInteger::add(&mut value, &one), Err(CapacityExceeded { required: 2 }));
+        assert_eq!(value.words(), &[Word(u64::MAX)]);
+        assert_eq!(value.checked_add(&one), Err(CapacityExceeded { required: 2 }));
+        assert_eq!(value.add_u64(1), Err(CapacityExceeded { required: 2 }));
+        assert_eq!(value.words(), &[Word(u64::MAX)]);
+        // Sums that fit still work
+        let mut small_value = one.clone();
+        UnsignedInteger::add(&mut small_value, &one).unwrap();
+        assert_eq!(small_value.words(), &[Word(2)]);
+        let mut empty = UnsignedInteger::<InlineWordArray<0>>::ZERO;
+        assert_eq!(empty.set(1), Err(CapacityExceeded { required: 1 }));
+        empty.set(0).unwrap();
+    }
+    #[test]
+    fn inline_carrying_add_at_capacity() {
+        // A full array only fails once the sum carries out of the top word
+        let mut value = UnsignedInteger::<InlineWordArray<1>>::try_from_u128(5).unwrap();
+        assert_eq!(value.carrying_add_u64(7, true), Ok(false));
+        assert_eq!(value.words(), &[Word(13)]);
+        let mut value = UnsignedInteger::<InlineWordArray<1>>::try_from_u128(u64::MAX as u128 - 1).unwrap();
+        assert_eq!(value.carrying_add_u64(0, true), Ok(false));
+        assert_eq!(value.words(), &[Word(u64::MAX)]);
+        assert_eq!(value.carrying_add_u64(0, true), Err(CapacityExceeded { required: 2 }));
+        assert_eq!(value.words(), &[Word(u64::MAX)]);
+        let mut empty = UnsignedInteger::<InlineWordArray<1>>::ZERO;
+        assert_eq!(empty.carrying_add_u64(u64::MAX, false), Ok(false));
+        let mut empty = UnsignedInteger::<InlineWordArray<1>>::ZERO;
+        assert_eq!(empty.carrying_add_u64(u64::MAX, true), Err(CapacityExceeded { required: 2 }));
+        let mut zero = UnsignedInteger::<InlineWordArray<0>>::ZERO;
+        assert_eq!(zero.carrying_add_u64(0, false), Ok(false));
+        assert!(zero.is_empty());
+        // With room for the carry, it's reported
+        let mut wide = UnsignedInteger::<InlineWordArray<2>>::try_from_u128(u64::MAX as u128).unwrap();
+        assert_eq!(wide.carrying_add_u64(1, false), Ok(true));
+        assert_eq!(wide.words(), &[Word(0), Word(1)]);
+    }
+    #[test]
+    fn overflowing_add_bits_boundary() {
+        for &bits in &[1u64, 63, 64, 65, 128] {
+            let max = UnsignedInteger::from(u128::MAX >> (128 - bits));
+            // The top bit of the sum lands at `bits - 1`, so it still fits
+            let mut fits = max.clone();
+            fits.sub_u64(1).unwrap();
+            assert!(!fits.overflowing_add_bits(&small(1), bits).unwrap());
+            assert_eq!(fits, max);
+            // The top bit lands at `bits`, wrapping around to zero
+            let mut wraps = max.clone();
+            assert!(wraps.overflowing_add_bits(&small(1), bits).unwrap());
+            assert!(wraps.is_empty(), "{} bits", bits);
+            // Doubling the maximum keeps every bit but the lowest
+            let mut doubled = max.clone();
+            assert!(doubled.overflowing_add_bits(&max, bits).unwrap());
+            let mut expected = max.clone();
+            expected.sub_u64(1).unwrap();
+            assert_eq!(doubled, expected);
+            assert_eq!(doubled.check_invariants(), Ok(()));
+        }
+    }
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_consistent_with_eq() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashMap;
+        fn hash_of<T: Hash>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+        const DIGITS: &str = "123456789012345678901234567890";
+        let parsed: UnsignedInteger = DIGITS.parse().unwrap();
+        let inline: UnsignedInteger<InlineWordArray<4>> = DIGITS.parse().unwrap();
+        let round_trip: UnsignedInteger = inline.convert().unwrap();
+        assert_eq!(hash_of(&parsed), hash_of(&round_trip));
+        // Padding the words doesn't change the hash once normalized
+        let mut padded = UnsignedInteger::from_word_array(vec![Word(5), Word(0), Word(0)]);
+        padded.normalize();
+        assert_eq!(hash_of(&padded), hash_of(&small(5)));
+        let mut map = HashMap::new();
+        map.insert(parsed, "big");
+        map.insert(small(0), "zero");
+        assert_eq!(map.get(&round_trip), Some(&"big"));
+        assert_eq!(map.get(&UnsignedInteger::ZERO), Some(&"zero"));
+        assert_eq!(map.get(&small(5)), None);
+    }
+    #[test]
+    fn to_f64() {
+        let mut rng = SmallRng::seed_from_u64(314);
+        for value in [0, 1, 2, (1 << 53) - 1, 1 << 53, (1 << 53) + 1, u64::MAX].iter().copied()
+            .chain((0..200).map(|_| rng.gen::<u64>())) {
+            assert_eq!(small(value).to_f64(), value as f64, "{}", value);
+        }
+        // Rounding past a word boundary matches the 128-bit cast
+        let halfway = (1u128 << 100) + (1 << 47);
+        for &value in &[halfway - 1, halfway, halfway + 1, halfway + (1 << 48), u128::MAX] {
+            assert_eq!(UnsignedInteger::<Vec<Word>>::from(value).to_f64(), value as f64, "{}", value);
+        }
+        // A sticky bit far below the top words still rounds up past halfway
+        let mut sticky = from_words(&[1, 0, 0, 1 << 63 | 1 << 10]);
+        assert_eq!(sticky.to_f64(), 2f64.powi(255) + 2f64.powi(203));
+        sticky.set_bit(0, false).unwrap();
+        // Exactly halfway, so ties go to the even mantissa
+        assert_eq!(sticky.to_f64(), 2f64.powi(255));
+        // The largest finite float, and just past it
+        let mut max = UnsignedInteger::<Vec<Word>>::from((1u64 << 53) - 1);
+        UnsignedInteger::shl_assign(&mut max, 1024 - 53).unwrap();
+        assert_eq!(max.to_f64(), f64::MAX);
+        let mut rounds_over = max.clone();
+        UnsignedInteger::add(&mut rounds_over, &(small(1) << (1024 - 54))).unwrap();
+        assert_eq!(rounds_over.to_f64(), f64::INFINITY);
+        assert_eq!((small(1) << 1024).to_f64(), f64::INFINITY);
+    }
+    #[test]
+    fn inc_and_dec() {
+        let mut value = small(u64::MAX - 1);
+        value.inc().unwrap();
+        assert_eq!(value, small(u64::MAX));
+        value.inc().unwrap();
+        assert_eq!(value, from_words(&[0, 1]));
+        value.dec().unwrap();
+        assert_eq!(value.words(), &[Word(u64::MAX)]);
+        let mut zero = small(1);
+        zero.dec().unwrap();
+        assert!(zero.is_empty());
+        assert!(matches!(zero.dec(), Err(SubError::Underflow)));
+        assert!(zero.is_empty());
+        // A fixed capacity only fails once the carry needs another word
+        let mut inline = UnsignedInteger::<InlineWordArray<1>>::try_from_u128(u64::MAX as u128).unwrap();
+        assert_eq!(inline.inc().unwrap_err(), CapacityExceeded { required: 2 });
+        inline.dec().unwrap();
+        assert_eq!(inline.words(), &[Word(u64::MAX - 1)]);
+    }
+    #[test]
+    fn sub_u64() {
+        let mut value = from_words(&[0, 1]);
+        value.sub_u64(1).unwrap();
+        assert_eq!(value.words(), &[Word(u64::MAX)]);
+        assert_eq!(value.check_invariants(), Ok(()));
+        // Borrowing through several zero words
+        let mut borrowed = from_words(&[5, 0, 0, 1]);
+        borrowed.sub_u64(6).unwrap();
+        assert_eq!(borrowed, from_words(&[u64::MAX, u64::MAX, u64::MAX]));
+        let mut exact = small(42);
+        exact.sub_u64(42).unwrap();
+        assert!(exact.is_empty());
+        let mut short = small(3);
+        assert!(matches!(short.sub_u64(4), Err(SubError::Underflow)));
+        assert_eq!(short, small(3));
+        let mut unchecked = from_words(&[2, 1]);
+        unchecked.unchecked_sub_u64(3);
+        assert_eq!(unchecked, small(u64::MAX));
+    }
+    #[test]
+    fn one_constructor() {
+        let one: UnsignedInteger = UnsignedInteger::one().unwrap();
+        assert_eq!(one, "1".parse().unwrap());
+        assert!(one.is_one());
+        assert_eq!(one.check_invariants(), Ok(()));
+        let inline = UnsignedInteger::<InlineWordArray<2>>::one().unwrap();
+        assert!(inline.is_one());
+        assert_eq!(inline, "1".parse().unwrap());
+        assert_eq!(UnsignedInteger::<InlineWordArray<0>>::one().unwrap_err(), CapacityExceeded { required: 1 });
+    }
+}